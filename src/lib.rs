@@ -1,27 +1,99 @@
 use std::path::Path;
+use std::sync::Arc;
 use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::Keycode;
 
+use crate::rendering::integrators::debug::DebugMode;
+use crate::rendering::integrators::integrator::IntegratorKind;
+use crate::rendering::lights::environment_light::EnvironmentMap;
+use crate::rendering::lights::sky_light::Sky;
 use crate::rendering::renderer::{Renderer, RendererProperties};
+use crate::rendering::samplers::sampler::SamplerKind;
 use crate::visual::display::Display;
 
 mod visual;
 mod rendering;
 
+// Samples per pixel of the final image and of the blue noise preview
+const SAMPLES: u32 = 100;
+const PREVIEW_SAMPLES: u32 = 4;
+
+// The number keys switch between the final image and the preview and debug views
+fn integrator_for_key(keycode: Keycode) -> Option<IntegratorKind> {
+    match keycode {
+        Keycode::NUM_1 => Some(IntegratorKind::PathTracer),
+        Keycode::NUM_2 => Some(IntegratorKind::AmbientOcclusion { distance: 1.0 }),
+        Keycode::NUM_3 => Some(IntegratorKind::Debug(DebugMode::Normals)),
+        Keycode::NUM_4 => Some(IntegratorKind::Debug(DebugMode::Depth { max_distance: 10.0 })),
+        Keycode::NUM_5 => Some(IntegratorKind::Debug(DebugMode::Uvs)),
+        Keycode::NUM_6 => Some(IntegratorKind::Debug(DebugMode::MaterialIds)),
+        Keycode::NUM_7 => Some(IntegratorKind::Debug(DebugMode::BvhCost { max_tests: 64 })),
+        Keycode::NUM_8 => Some(IntegratorKind::Debug(DebugMode::Bounces)),
+        _ => None,
+    }
+}
+
 pub struct Application {
     display: Display,
     renderer: Renderer,
+    sampler: SamplerKind,
+    samples: u32,
+    preview: bool,
 }
 
 impl Application {
     pub fn new(width: u32, height: u32) -> Result<Self, String> {
+        // Afternoon daylight unless a panorama replaces it
+        let mut renderer = Renderer::new(RendererProperties::new((width, height), SAMPLES, 8, 2.0));
+        renderer.set_sky(Some(Sky::new(0.6, 0.8, 2.5)));
+
         Ok(Application {
             display: Display::new(width, height)?,
-            renderer: Renderer::new(RendererProperties::new((width, height), 100, 8, 2.0)),
+            renderer,
+            sampler: SamplerKind::Sobol,
+            samples: SAMPLES,
+            preview: false,
         })
     }
 
+    // Sampler and samples per pixel of the final image, blue noise with 1 to 4 samples gives quick
+    // previews
+    pub fn set_sampler(&mut self, sampler: SamplerKind, samples: u32) {
+        self.sampler = sampler;
+        self.samples = samples;
+        self.update_sampler();
+    }
+
+    // Renders with the same seed give the same image
+    pub fn set_seed(&mut self, seed: u64) {
+        self.renderer.set_seed(seed);
+    }
+
+    fn update_sampler(&mut self) {
+        if self.preview {
+            self.renderer.set_sampler(SamplerKind::BlueNoise);
+            self.renderer.set_samples(PREVIEW_SAMPLES);
+        } else {
+            self.renderer.set_sampler(self.sampler);
+            self.renderer.set_samples(self.samples);
+        }
+    }
+
+    pub fn set_sky(&mut self, sky: Sky) {
+        self.renderer.set_sky(Some(sky));
+    }
+
+    // Lights the scene with an equirectangular HDR or EXR panorama instead of the plain sky
+    pub fn set_environment(&mut self, path: &Path) -> Result<(), String> {
+        let environment = EnvironmentMap::load(path)?;
+        self.renderer.set_environment(Some(Arc::new(environment)));
+        Ok(())
+    }
+
     fn render_loop(&mut self) {
         println!("Close Window to stop the Program and save the current frame!");
+        println!("Keys 1 to 8 switch between path tracing, ambient occlusion, normals, depth, uvs, material ids, bvh cost and bounces");
+        println!("P switches between the final image and a blue noise preview with {} samples per pixel", PREVIEW_SAMPLES);
         let (mut queue_mutex, mut handles, mut stop) = self.renderer.start_render();
 
         let context = self.display.get_context();
@@ -40,6 +112,21 @@ impl Application {
                         self.renderer.set_size((width as u32, height as u32));
                         (queue_mutex, handles, stop) = self.renderer.start_render();
                     }
+                    Event::KeyDown {keycode: Some(Keycode::P), repeat: false, ..} => {
+                        *stop.lock().unwrap() = true;
+                        self.display.clear();
+                        self.preview = !self.preview;
+                        self.update_sampler();
+                        (queue_mutex, handles, stop) = self.renderer.start_render();
+                    }
+                    Event::KeyDown {keycode: Some(keycode), repeat: false, ..} => {
+                        if let Some(integrator) = integrator_for_key(keycode) {
+                            *stop.lock().unwrap() = true;
+                            self.display.clear();
+                            self.renderer.set_integrator(integrator);
+                            (queue_mutex, handles, stop) = self.renderer.start_render();
+                        }
+                    }
                     _ => {}
                 }
             }
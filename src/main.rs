@@ -1,8 +1,14 @@
+use std::path::Path;
 use rendering_engine::Application;
 
 fn main() -> Result<(), String>{
     let mut application = Application::new(1080, 920)?;
 
+    // An optional HDR panorama lights the scene
+    if let Some(path) = std::env::args().nth(1) {
+        application.set_environment(Path::new(&path))?;
+    }
+
     application.start();
 
     Ok(())
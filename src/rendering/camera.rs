@@ -1,4 +1,5 @@
 use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
 use crate::rendering::vector_math::Vec3;
 
 pub struct Camera {
@@ -10,6 +11,9 @@ pub struct Camera {
     screen_size: (u32,u32),
     viewport_height: f32,
     focal_length: f32,
+
+    shutter_open: f32,
+    shutter_close: f32,
 }
 
 impl Camera {
@@ -23,18 +27,90 @@ impl Camera {
         let vertical = Vec3(0.0, viewport_height, 0.0);
         let top_left = origin - horizontal/2.0 + vertical/2.0 - Vec3(0.0, 0.0, focal_length);
 
-        Self { top_left, origin, vertical, horizontal, screen_size, viewport_height, focal_length }
+        Self { top_left, origin, vertical, horizontal, screen_size, viewport_height, focal_length, shutter_open: 0.0, shutter_close: 0.0 }
+    }
+
+    // Rays get a random time between opening and closing, so moving objects smear over the frame
+    pub fn set_shutter(&mut self, shutter_open: f32, shutter_close: f32) {
+        self.shutter_open = shutter_open;
+        self.shutter_close = shutter_close;
+    }
+
+    // The sampler picks the time within the shutter interval
+    pub fn get_ray(&self, u: f32, v: f32, sampler: &mut dyn Sampler) -> Ray {
+        let time = self.shutter_open + (self.shutter_close - self.shutter_open) * sampler.get_1d();
+        Ray::new(self.origin, self.top_left - self.vertical*u + self.horizontal*v - self.origin, time)
+    }
+
+    pub fn shutter(&self) -> (f32, f32) {
+        (self.shutter_open, self.shutter_close)
+    }
+
+    pub fn origin(&self) -> Vec3 {
+        self.origin
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        Vec3(0.0, 0.0, -1.0)
+    }
+
+    // Pixels are spaced by the viewport divided by one less than the pixel count, so the film
+    // reaches a bit past the viewport
+    fn film_area(&self) -> f32 {
+        let width = self.horizontal.length() * self.screen_size.0 as f32 / (self.screen_size.0 - 1) as f32;
+        let height = self.vertical.length() * self.screen_size.1 as f32 / (self.screen_size.1 - 1) as f32;
+        width * height
+    }
+
+    // Pixel the point shows up on, None if it's behind the camera or outside the film
+    pub fn raster(&self, point: Vec3) -> Option<(u32, u32)> {
+        let direction = point - self.origin;
+        let depth = direction * self.forward();
+        if depth <= 0.0 {
+            return None;
+        }
+
+        let on_film = self.origin + direction * (self.focal_length / depth) - self.top_left;
+        let u = -(on_film * self.vertical) / self.vertical.length_squared();
+        let v = (on_film * self.horizontal) / self.horizontal.length_squared();
+        if u < 0.0 || v < 0.0 {
+            return None;
+        }
+
+        let x = (v * (self.screen_size.0 - 1) as f32) as u32;
+        let y = (u * (self.screen_size.1 - 1) as f32) as u32;
+        if x >= self.screen_size.0 || y >= self.screen_size.1 {
+            return None;
+        }
+        Some((x, y))
+    }
+
+    // Density over solid angle of get_ray picking the direction for a uniformly random pixel
+    pub fn pdf_direction(&self, direction: Vec3) -> f32 {
+        let cosine = direction.unit() * self.forward();
+        if cosine <= 0.0 || self.raster(self.origin + direction).is_none() {
+            return 0.0;
+        }
+        self.focal_length * self.focal_length / (self.film_area() * cosine * cosine * cosine)
     }
 
-    pub fn get_ray(&self, u: f32, v: f32) -> Ray {
-        Ray::new(self.origin, self.top_left - self.vertical*u + self.horizontal*v - self.origin)
+    // Sensitivity of the film to light arriving along the direction, normalized so that a pixel
+    // records the average radiance over the rays through it
+    pub fn importance(&self, direction: Vec3) -> f32 {
+        let cosine = direction.unit() * self.forward();
+        if cosine <= 0.0 {
+            return 0.0;
+        }
+        self.pdf_direction(direction) / cosine
     }
 }
 
 impl Clone for Camera {
     fn clone(&self) -> Self {
-        Camera::new(self.screen_size, self.viewport_height,self.focal_length)
+        let mut camera = Camera::new(self.screen_size, self.viewport_height,self.focal_length);
+        camera.set_shutter(self.shutter_open, self.shutter_close);
+        camera
     }
 }
 
-impl Copy for Camera {}
\ No newline at end of file
+impl Copy for Camera {}
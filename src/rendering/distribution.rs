@@ -0,0 +1,88 @@
+// Piecewise constant density over [0, 1) following the values of a function, which must not be
+// negative. Functions that are zero everywhere are sampled uniformly.
+pub struct Distribution1D {
+    function: Vec<f32>,
+    cdf: Vec<f32>,
+    integral: f32,
+}
+
+impl Distribution1D {
+    pub fn new(function: Vec<f32>) -> Self {
+        let count = function.len() as f32;
+        let mut cdf = Vec::with_capacity(function.len() + 1);
+        cdf.push(0.0);
+        for value in &function {
+            cdf.push(cdf.last().unwrap() + value / count);
+        }
+
+        let integral = *cdf.last().unwrap();
+        for (index, value) in cdf.iter_mut().enumerate() {
+            *value = if integral > 0.0 { *value / integral } else { index as f32 / count };
+        }
+
+        Self { function, cdf, integral }
+    }
+
+    pub fn integral(&self) -> f32 {
+        self.integral
+    }
+
+    fn len(&self) -> usize {
+        self.function.len()
+    }
+
+    // Density at x
+    pub fn pdf(&self, x: f32) -> f32 {
+        let index = ((x * self.len() as f32) as usize).min(self.len() - 1);
+        if self.integral > 0.0 {
+            self.function[index] / self.integral
+        } else {
+            1.0
+        }
+    }
+
+    // Turns a uniform random number into x with its density
+    pub fn sample(&self, random: f32) -> (f32, f32) {
+        // Last entry of the cdf not above the random number
+        let index = self.cdf.partition_point(|&value| value <= random).clamp(1, self.len()) - 1;
+
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let offset = if width > 0.0 { (random - self.cdf[index]) / width } else { 0.0 };
+        let x = ((index as f32 + offset) / self.len() as f32).min(1.0 - f32::EPSILON);
+
+        (x, self.pdf(x))
+    }
+}
+
+// Piecewise constant density over [0, 1)^2 following a function given row by row. Samples pick a
+// row first and then a column within it.
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(function: &[f32], width: usize, height: usize) -> Self {
+        let rows: Vec<Distribution1D> = function.chunks(width).take(height)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+        let marginal = Distribution1D::new(rows.iter().map(|row| row.integral()).collect());
+
+        Self { rows, marginal }
+    }
+
+    fn row(&self, y: f32) -> &Distribution1D {
+        &self.rows[((y * self.rows.len() as f32) as usize).min(self.rows.len() - 1)]
+    }
+
+    pub fn pdf(&self, x: f32, y: f32) -> f32 {
+        self.marginal.pdf(y) * self.row(y).pdf(x)
+    }
+
+    // Turns two uniform random numbers into (x, y) with its density
+    pub fn sample(&self, random: (f32, f32)) -> ((f32, f32), f32) {
+        let (y, row_pdf) = self.marginal.sample(random.1);
+        let (x, column_pdf) = self.row(y).sample(random.0);
+        ((x, y), row_pdf * column_pdf)
+    }
+}
@@ -0,0 +1,70 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
+use crate::rendering::integrators::integrator::Splat;
+use crate::rendering::raytracing::{float_to_u8_color, gamma_correction, Pixel};
+use crate::rendering::vector_math::Vec3;
+
+// Steps splats are rounded to, in parts of one
+const SPLAT_SCALE: f32 = (1 << 20) as f32;
+
+fn to_fixed(value: f32) -> i64 {
+    (value * SPLAT_SCALE).round() as i64
+}
+
+// Sum of the samples of every pixel. Camera samples belong to the line a thread is rendering and
+// are added once per pass over the image, splats can land on any line and keep arriving until the
+// last line is done. Splats are summed as fixed point numbers, so the sum doesn't depend on the
+// order the threads deliver them in.
+pub struct Film {
+    size: (u32, u32),
+    samples: Vec<u32>,
+    colors: Vec<Vec3>,
+    splats: Vec<[i64; 3]>,
+    has_splats: bool,
+}
+
+impl Film {
+    pub fn new(size: (u32, u32)) -> Self {
+        let pixels = (size.0 * size.1) as usize;
+        Self { size, samples: vec![0; pixels], colors: vec![Vec3(0.0, 0.0, 0.0); pixels], splats: vec![[0; 3]; pixels], has_splats: false }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y * self.size.0 + x) as usize
+    }
+
+    // Adds the sum of some samples of the pixel
+    pub fn add_color(&mut self, x: u32, y: u32, color: Vec3, samples: u32) {
+        let index = self.index(x, y);
+        self.colors[index] = self.colors[index] + color;
+        self.samples[index] += samples;
+    }
+
+    // Takes all splats out of the list
+    pub fn add_splats(&mut self, splats: &mut Vec<Splat>) {
+        for splat in splats.drain(..) {
+            let index = self.index(splat.x, splat.y);
+            let sum = &mut self.splats[index];
+            sum[0] += to_fixed(splat.radiance.0);
+            sum[1] += to_fixed(splat.radiance.1);
+            sum[2] += to_fixed(splat.radiance.2);
+            self.has_splats = true;
+        }
+    }
+
+    pub fn has_splats(&self) -> bool {
+        self.has_splats
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Pixel {
+        let index = self.index(x, y);
+        let splats = self.splats[index];
+        let splats = Vec3(splats[0] as f32, splats[1] as f32, splats[2] as f32) / SPLAT_SCALE;
+        let color = float_to_u8_color(gamma_correction(self.colors[index] + splats, self.samples[index].max(1)));
+        Pixel::new(Point::new(x as i32, y as i32), Color::RGB(color.0, color.1, color.2))
+    }
+
+    pub fn pixels(&self) -> Vec<Pixel> {
+        (0..self.size.1).flat_map(|y| (0..self.size.0).map(move |x| (x, y))).map(|(x, y)| self.pixel(x, y)).collect()
+    }
+}
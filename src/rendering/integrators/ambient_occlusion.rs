@@ -0,0 +1,70 @@
+use crate::rendering::integrators::integrator::Integrator;
+use crate::rendering::onb::Onb;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// White where the hemisphere above the first hit is open, darker the more of it is blocked within
+// the given distance
+pub struct AmbientOcclusion {
+    distance: f32,
+}
+
+impl AmbientOcclusion {
+    pub fn new(distance: f32) -> Self {
+        Self { distance }
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut hit_record = HitRecord::new();
+        if !scene.hit(ray, 0.001, f32::INFINITY, &mut hit_record) {
+            return Vec3(1.0, 1.0, 1.0);
+        }
+
+        // Cosine weighted directions make the average of the visibility the occlusion itself
+        let direction = Onb::new(hit_record.normal).local(Vec3::sample_cosine_direction(sampler.get_2d()));
+        let occlusion_ray = Ray::new(hit_record.position, direction, ray.time());
+
+        let mut blocker = HitRecord::new();
+        if scene.hit(&occlusion_ray, 0.001, self.distance / direction.length(), &mut blocker) {
+            Vec3(0.0, 0.0, 0.0)
+        } else {
+            Vec3(1.0, 1.0, 1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::integrators::testing::{average, close, plane};
+    use crate::rendering::materials::lambertian::Lambertian;
+    use crate::rendering::shapes::bvh::Bvh;
+
+    fn floor(ceiling: bool) -> Scene {
+        let mut objects = vec![plane(0.0, true, Box::new(Lambertian::new(Vec3(0.5, 0.5, 0.5))))];
+        if ceiling {
+            objects.push(plane(0.5, false, Box::new(Lambertian::new(Vec3(0.5, 0.5, 0.5)))));
+        }
+        Scene::new(Box::new(Bvh::new(objects)))
+    }
+
+    #[test]
+    fn open_sky_is_white() {
+        let ray = Ray::new(Vec3(0.0, 0.0, 1.0), Vec3(0.3, 0.2, -1.0), 0.0);
+        assert!(close(average(&AmbientOcclusion::new(1.0), &ray, &floor(false), 64), 1.0, 1e-6));
+    }
+
+    #[test]
+    fn ceiling_blocks_the_steep_directions() {
+        // Directions within 60 degrees of the normal reach the ceiling half a unit above within a
+        // distance of one, which is three quarters of the cosine weighted hemisphere
+        let ray = Ray::new(Vec3(0.0, 0.0, 0.25), Vec3(0.1, 0.0, -1.0), 0.0);
+        let occlusion = average(&AmbientOcclusion::new(1.0), &ray, &floor(true), 4000);
+        assert!(close(occlusion, 0.25, 0.02));
+    }
+}
@@ -0,0 +1,513 @@
+use rand::Rng;
+use crate::rendering::camera::Camera;
+use crate::rendering::integrators::integrator::{CameraSample, Integrator, Splat};
+use crate::rendering::materials::material::Material;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Light vertices know the index of their light in the scene
+enum VertexKind {
+    Camera,
+    Light(usize),
+    Surface,
+}
+
+// Point of a camera or light subpath. Beta is the throughput from the start of the subpath up to
+// the vertex. Both densities are over area: pdf_forward of the own subpath reaching the vertex,
+// pdf_reverse of the other subpath reaching it when sampled from the opposite direction.
+// Vertices of infinite lights have no area, densities of reaching them stay over solid angle.
+struct Vertex<'a> {
+    kind: VertexKind,
+    record: HitRecord<'a>,
+    incoming: Vec3,
+    beta: Vec3,
+    pdf_forward: f32,
+    pdf_reverse: f32,
+    delta: bool,
+    infinite: bool,
+}
+
+impl<'a> Vertex<'a> {
+    fn endpoint(kind: VertexKind, position: Vec3, normal: Vec3, beta: Vec3) -> Self {
+        let mut record = HitRecord::new();
+        record.position = position;
+        record.normal = normal;
+        record.font_face = true;
+
+        Self { kind, record, incoming: Vec3(0.0, 0.0, 0.0), beta, pdf_forward: 0.0, pdf_reverse: 0.0, delta: false, infinite: false }
+    }
+
+    fn position(&self) -> Vec3 {
+        self.record.position
+    }
+
+    fn material(&self) -> &'a dyn Material {
+        self.record.material.unwrap()
+    }
+
+    fn on_surface(&self) -> bool {
+        match self.kind {
+            VertexKind::Camera => false,
+            VertexKind::Light(_) => true,
+            VertexKind::Surface => !self.material().is_volume(),
+        }
+    }
+
+    // Record as seen from the side from points to. Connections can reach a vertex from the other
+    // side than the ray that found it, e.g. light inside of glass.
+    fn facing(&self, from: Vec3) -> HitRecord<'a> {
+        let mut record = self.record.clone();
+        if !self.material().is_volume() && record.normal * from < 0.0 {
+            record.normal = -1.0 * record.normal;
+            record.font_face = !record.font_face;
+        }
+        record
+    }
+
+    // Material for light arriving from to and leaving towards from, both pointing away from the
+    // vertex
+    fn eval(&self, from: Vec3, to: Vec3) -> Vec3 {
+        let ray = Ray::new(self.position() + from, -1.0 * from, 0.0);
+        self.material().eval(&ray, &self.facing(from), to)
+    }
+
+    fn scatter_pdf(&self, from: Vec3, to: Vec3) -> f32 {
+        let ray = Ray::new(self.position() + from, -1.0 * from, 0.0);
+        self.material().pdf(&ray, &self.facing(from), to)
+    }
+}
+
+// Turns a density over solid angle at from into one over the area around to
+fn area_density(pdf: f32, from: &Vertex, to: &Vertex) -> f32 {
+    if to.infinite {
+        return pdf;
+    }
+
+    let offset = to.position() - from.position();
+    let distance_squared = offset.length_squared();
+    if distance_squared == 0.0 {
+        return 0.0;
+    }
+
+    if to.on_surface() {
+        pdf * (to.record.normal * offset).abs() / (distance_squared * distance_squared.sqrt())
+    } else {
+        pdf / distance_squared
+    }
+}
+
+// Densities of a light path starting at position and leaving along direction: the light and the
+// point on it over area, the direction over solid angle. Zero if no light covers the position.
+// Delta and infinite lights can't be found by position, they only show up as light vertices.
+fn emission_density(scene: &Scene, position: Vec3, direction: Vec3) -> (f32, f32) {
+    match scene.light_at(position, direction) {
+        Some(index) => {
+            let (position_pdf, direction_pdf) = scene.lights[index].emission_pdf(position, direction);
+            (position_pdf / scene.lights.len() as f32, direction_pdf)
+        }
+        None => (0.0, 0.0),
+    }
+}
+
+// Density of the light path starting at vertex and leaving along direction. It's over the area of
+// the light, except for infinite lights where it's over the directions the light arrives from.
+fn origin_density(scene: &Scene, vertex: &Vertex, direction: Vec3) -> f32 {
+    let light = match vertex.kind {
+        VertexKind::Light(index) => &scene.lights[index],
+        _ => return emission_density(scene, vertex.position(), direction).0,
+    };
+
+    let (position_pdf, direction_pdf) = light.emission_pdf(vertex.position(), direction);
+    let pdf = if light.is_infinite() { direction_pdf } else { position_pdf };
+    pdf / scene.lights.len() as f32
+}
+
+// Density of the light at vertex emitting towards next, over the area around next
+fn light_density(scene: &Scene, vertex: &Vertex, next: &Vertex) -> f32 {
+    let direction = (next.position() - vertex.position()).unit();
+    let light = match vertex.kind {
+        VertexKind::Light(index) => &scene.lights[index],
+        _ => return area_density(emission_density(scene, vertex.position(), direction).1, vertex, next),
+    };
+
+    let (position_pdf, direction_pdf) = light.emission_pdf(vertex.position(), direction);
+    if !light.is_infinite() {
+        return area_density(direction_pdf, vertex, next);
+    }
+
+    // Parallel rays from the disk of an infinite light hit next with the density of the disk,
+    // spread out by the angle they hit at
+    if next.on_surface() {
+        position_pdf * (next.record.normal * direction).abs()
+    } else {
+        position_pdf
+    }
+}
+
+// Light paths can't reach delta lights, so the camera path never finds them on its own
+fn is_delta_light(scene: &Scene, vertex: &Vertex) -> bool {
+    match vertex.kind {
+        VertexKind::Light(index) => scene.lights[index].is_delta(),
+        _ => false,
+    }
+}
+
+// Density of vertex picking next when reached from previous, over the area around next
+fn density(scene: &Scene, camera: Option<&Camera>, vertex: &Vertex, previous: Option<&Vertex>, next: &Vertex) -> f32 {
+    let to = (next.position() - vertex.position()).unit();
+    let pdf = match vertex.kind {
+        VertexKind::Camera => camera.map_or(0.0, |camera| camera.pdf_direction(to)),
+        VertexKind::Light(_) => return light_density(scene, vertex, next),
+        VertexKind::Surface => {
+            let from = previous.map_or(-1.0 * vertex.incoming, |previous| (previous.position() - vertex.position()).unit());
+            vertex.scatter_pdf(from, to)
+        }
+    };
+    area_density(pdf, vertex, next)
+}
+
+// Stops a bit in front of the target, so it doesn't count as a blocker itself
+fn unoccluded(scene: &Scene, from: Vec3, to: Vec3, time: f32) -> bool {
+    let offset = to - from;
+    let distance = offset.length();
+    let mut blocker = HitRecord::new();
+    !scene.hit(&Ray::new(from, offset / distance, time), 0.001, distance * (1.0 - 1e-4), &mut blocker)
+}
+
+// Traces a path from the camera and one from a light, then connects every vertex of the one with
+// every vertex of the other. Each connection is a different way of finding the same kind of path,
+// so they are combined with multiple importance sampling (balance heuristic). Connections straight
+// to the camera land on other pixels and are handed to the renderer as splats, which needs the
+// camera and only happens through sample_radiance.
+pub struct Bidirectional {
+    max_bounces: u32,
+}
+
+impl Bidirectional {
+    pub fn new(max_bounces: u32) -> Self {
+        Self { max_bounces }
+    }
+
+    // Extends the path until it escapes, gets absorbed or has max_vertices vertices. Rays escaping
+    // to the sky add to sky, only the camera path passes it in. If the sky is an environment light
+    // the path ends on a vertex of it instead, so the other strategies can weigh in.
+    #[allow(clippy::too_many_arguments)]
+    fn random_walk<'a>(&self, scene: &'a Scene, ray: &Ray, beta: Vec3, pdf: f32, max_vertices: usize, path: &mut Vec<Vertex<'a>>, mut sky: Option<&mut Vec3>, sampler: &mut dyn Sampler) {
+        let mut ray = Ray::new(ray.origin(), ray.direction(), ray.time());
+        let mut beta = beta;
+        let mut pdf = pdf;
+
+        while path.len() < max_vertices {
+            let mut record = HitRecord::new();
+            if !scene.hit(&ray, 0.001, f32::INFINITY, &mut record) {
+                match (sky.as_mut(), scene.environment()) {
+                    (Some(_), Some(index)) => {
+                        // Only the direction of the vertex matters, so it sits right in front of the ray
+                        let direction = ray.direction().unit();
+                        let mut vertex = Vertex::endpoint(VertexKind::Light(index), ray.origin() + direction, -1.0 * direction, beta);
+                        vertex.incoming = direction;
+                        vertex.infinite = true;
+                        vertex.pdf_forward = pdf;
+                        path.push(vertex);
+                    }
+                    (Some(sky), None) => **sky = **sky + beta.mul(&scene.background(&ray)),
+                    _ => {}
+                }
+                break;
+            }
+
+            let incoming = ray.direction().unit();
+            let mut vertex = Vertex { kind: VertexKind::Surface, record, incoming, beta, pdf_forward: 0.0, pdf_reverse: 0.0, delta: false, infinite: false };
+            vertex.pdf_forward = area_density(pdf, path.last().unwrap(), &vertex);
+            path.push(vertex);
+            if path.len() >= max_vertices {
+                break;
+            }
+
+            let current = path.len() - 1;
+            let scatter = match path[current].material().sample(&ray, &path[current].record, sampler) {
+                Some(scatter) => scatter,
+                None => break,
+            };
+
+            beta = beta.mul(&scatter.weight);
+            if beta.near_zero() {
+                break;
+            }
+
+            let direction = scatter.direction.unit();
+            let reverse = if scatter.pdf > 0.0 {
+                pdf = scatter.pdf;
+                path[current].scatter_pdf(direction, -1.0 * incoming)
+            } else {
+                path[current].delta = true;
+                pdf = 0.0;
+                0.0
+            };
+            path[current - 1].pdf_reverse = area_density(reverse, &path[current], &path[current - 1]);
+
+            ray = Ray::new(path[current].position(), scatter.direction, ray.time());
+        }
+    }
+
+    fn light_path<'a>(&self, scene: &'a Scene, time: f32, sampler: &mut dyn Sampler) -> Vec<Vertex<'a>> {
+        let mut path = Vec::new();
+        if scene.lights.is_empty() {
+            return path;
+        }
+
+        let index = random::rng().gen_range(0..scene.lights.len());
+        let emission = match scene.lights[index].sample_emission() {
+            Some(emission) => emission,
+            None => return path,
+        };
+
+        let origin_pdf = emission.position_pdf / scene.lights.len() as f32;
+        if origin_pdf <= 0.0 || emission.direction_pdf <= 0.0 || emission.radiance.near_zero() {
+            return path;
+        }
+
+        let mut vertex = Vertex::endpoint(VertexKind::Light(index), emission.position, emission.normal, emission.radiance);
+        vertex.infinite = scene.lights[index].is_infinite();
+        vertex.pdf_forward = origin_density(scene, &vertex, emission.direction);
+        path.push(vertex);
+
+        let cosine = (emission.normal * emission.direction).abs();
+        let beta = emission.radiance * (cosine / (origin_pdf * emission.direction_pdf));
+        let ray = Ray::new(emission.position, emission.direction, time);
+        self.random_walk(scene, &ray, beta, emission.direction_pdf, self.max_bounces as usize + 1, &mut path, None, sampler);
+
+        // Infinite lights don't start their rays from a point, so the density of the first hit has
+        // to come from the light itself
+        if path.len() > 1 {
+            path[1].pdf_forward = light_density(scene, &path[0], &path[1]);
+        }
+
+        path
+    }
+
+    // Radiance of the path made of the first s light and first t camera vertices, weighted by mis.
+    // Connections to the camera go to splats instead.
+    #[allow(clippy::too_many_arguments)]
+    fn connect(&self, scene: &Scene, camera: Option<&Camera>, light_path: &[Vertex], camera_path: &[Vertex], s: usize, t: usize, time: f32, splats: &mut Vec<Splat>) -> Vec3 {
+        let black = Vec3(0.0, 0.0, 0.0);
+
+        if s == 0 {
+            // The camera path found a light on its own
+            let pt = &camera_path[t - 1];
+            let emitted = match pt.kind {
+                VertexKind::Surface => pt.material().emitted(&pt.record),
+                VertexKind::Light(index) => scene.lights[index].radiance(pt.incoming),
+                VertexKind::Camera => return black,
+            };
+            let radiance = pt.beta.mul(&emitted);
+            if radiance.near_zero() {
+                return black;
+            }
+            return radiance * self.mis_weight(scene, camera, light_path, camera_path, None, s, t);
+        }
+
+        if t == 1 {
+            // The light path is seen by the camera
+            let camera = match camera {
+                Some(camera) => camera,
+                None => return black,
+            };
+            let qs = &light_path[s - 1];
+            let (x, y) = match camera.raster(qs.position()) {
+                Some(raster) => raster,
+                None => return black,
+            };
+
+            let offset = camera.origin() - qs.position();
+            let direction = offset.unit();
+            let importance = camera.importance(-1.0 * offset) * (camera.forward() * -1.0 * direction);
+            let radiance = qs.beta.mul(&qs.eval(-1.0 * qs.incoming, direction)) * (importance / offset.length_squared());
+            if radiance.near_zero() || !unoccluded(scene, qs.position(), camera.origin(), time) {
+                return black;
+            }
+
+            let sampled = Vertex::endpoint(VertexKind::Camera, camera.origin(), camera.forward(), Vec3(1.0, 1.0, 1.0));
+            let weight = self.mis_weight(scene, Some(camera), light_path, camera_path, Some(&sampled), s, t);
+            splats.push(Splat { x, y, radiance: radiance * weight });
+            return black;
+        }
+
+        let pt = &camera_path[t - 1];
+        if !matches!(pt.kind, VertexKind::Surface) {
+            return black;
+        }
+
+        if s == 1 {
+            // Next event estimation, a freshly sampled point on a light replaces the light path
+            let index = random::rng().gen_range(0..scene.lights.len());
+            let light = &scene.lights[index];
+            let sample = match light.sample(pt.position()) {
+                Some(sample) => sample,
+                None => return black,
+            };
+
+            let light_pdf = sample.pdf / scene.lights.len() as f32;
+            let value = pt.eval(-1.0 * pt.incoming, sample.direction);
+            let radiance = pt.beta.mul(&value).mul(&sample.radiance) / light_pdf;
+            let position = pt.position() + sample.direction * sample.distance;
+            if radiance.near_zero() || !unoccluded(scene, pt.position(), position, time) {
+                return black;
+            }
+
+            let mut sampled = Vertex::endpoint(VertexKind::Light(index), position, sample.normal, sample.radiance);
+            sampled.infinite = light.is_infinite();
+            sampled.pdf_forward = origin_density(scene, &sampled, -1.0 * sample.direction);
+            return radiance * self.mis_weight(scene, camera, light_path, camera_path, Some(&sampled), s, t);
+        }
+
+        let qs = &light_path[s - 1];
+        let offset = pt.position() - qs.position();
+        let direction = offset.unit();
+        let value = qs.eval(-1.0 * qs.incoming, direction).mul(&pt.eval(-1.0 * pt.incoming, -1.0 * direction));
+        let radiance = qs.beta.mul(&value).mul(&pt.beta) / offset.length_squared();
+        if radiance.near_zero() || !unoccluded(scene, qs.position(), pt.position(), time) {
+            return black;
+        }
+
+        radiance * self.mis_weight(scene, camera, light_path, camera_path, None, s, t)
+    }
+
+    // Balance heuristic over all other s and t that could have made the same path. Walking outwards
+    // from the connection, each step swaps one vertex from one subpath to the other, which scales
+    // the density of the whole path by pdf_reverse / pdf_forward of that vertex. Sampled replaces
+    // the endpoint of the one vertex subpath.
+    #[allow(clippy::too_many_arguments)]
+    fn mis_weight(&self, scene: &Scene, camera: Option<&Camera>, light_path: &[Vertex], camera_path: &[Vertex], sampled: Option<&Vertex>, s: usize, t: usize) -> f32 {
+        if s + t == 2 {
+            return 1.0;
+        }
+
+        let qs = match s {
+            0 => None,
+            1 => sampled,
+            _ => Some(&light_path[s - 1]),
+        };
+        let pt = if t == 1 { sampled.unwrap() } else { &camera_path[t - 1] };
+        let qs_minus = if s > 1 { Some(&light_path[s - 2]) } else { None };
+        let pt_minus = if t > 1 { Some(&camera_path[t - 2]) } else { None };
+
+        // Forward density, reverse density and delta of every vertex, updated for this connection
+        let densities = |vertex: &Vertex| (vertex.pdf_forward, vertex.pdf_reverse, vertex.delta);
+        let mut lights: Vec<(f32, f32, bool)> = light_path[..s.saturating_sub(1)].iter().map(densities).collect();
+        let mut cameras: Vec<(f32, f32, bool)> = camera_path[..t - 1].iter().map(densities).collect();
+        cameras.push((pt.pdf_forward, 0.0, false));
+
+        if let Some(qs) = qs {
+            lights.push((qs.pdf_forward, density(scene, camera, pt, pt_minus, qs), false));
+            if let Some(qs_minus) = qs_minus {
+                lights[s - 2].1 = density(scene, camera, qs, Some(pt), qs_minus);
+            }
+        }
+
+        match qs {
+            Some(qs) => cameras[t - 1].1 = density(scene, camera, qs, qs_minus, pt),
+            None => {
+                // Emitters the lights don't know about can only be found by the camera path
+                let towards = pt_minus.map_or(-1.0 * pt.incoming, |pt_minus| (pt_minus.position() - pt.position()).unit());
+                cameras[t - 1].1 = origin_density(scene, pt, towards);
+                if cameras[t - 1].1 == 0.0 {
+                    return 1.0;
+                }
+            }
+        }
+
+        if let Some(pt_minus) = pt_minus {
+            cameras[t - 2].1 = match qs {
+                Some(qs) => density(scene, camera, pt, Some(qs), pt_minus),
+                None => light_density(scene, pt, pt_minus),
+            };
+        }
+
+        // Densities of zero come from specular vertices, which are skipped as connection points
+        // but still have to pass the ratio along
+        let remap = |pdf: f32| if pdf != 0.0 { pdf } else { 1.0 };
+        let mut sum = 0.0;
+
+        let mut ratio = 1.0;
+        for i in (1..t).rev() {
+            ratio *= remap(cameras[i].1) / remap(cameras[i].0);
+            // A single camera vertex means splatting, which only works with the camera at hand
+            if !cameras[i].2 && !cameras[i - 1].2 && (i > 1 || camera.is_some()) {
+                sum += ratio;
+            }
+        }
+
+        let delta_light = match s {
+            0 => false,
+            1 => is_delta_light(scene, qs.unwrap()),
+            _ => is_delta_light(scene, &light_path[0]),
+        };
+
+        let mut ratio = 1.0;
+        for i in (0..s).rev() {
+            ratio *= remap(lights[i].1) / remap(lights[i].0);
+            let previous_delta = if i > 0 { lights[i - 1].2 } else { delta_light };
+            if !lights[i].2 && !previous_delta {
+                sum += ratio;
+            }
+        }
+
+        1.0 / (1.0 + sum)
+    }
+
+    fn trace(&self, ray: &Ray, scene: &Scene, camera: Option<&Camera>, splats: &mut Vec<Splat>, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut radiance = Vec3(0.0, 0.0, 0.0);
+
+        // Camera rays carry a weight of one, the film divides by the number of samples
+        let mut camera_path = vec![Vertex::endpoint(VertexKind::Camera, ray.origin(), Vec3(0.0, 0.0, 0.0), Vec3(1.0, 1.0, 1.0))];
+        let pdf = camera.map_or(1.0, |camera| camera.pdf_direction(ray.direction()));
+        self.random_walk(scene, ray, Vec3(1.0, 1.0, 1.0), pdf, self.max_bounces as usize + 2, &mut camera_path, Some(&mut radiance), sampler);
+
+        let light_path = self.light_path(scene, ray.time(), sampler);
+
+        // Connections to a single light vertex sample their own, so they don't need the light path
+        let light_vertices = if scene.lights.is_empty() { 0 } else { light_path.len().max(1) };
+
+        for t in 1..=camera_path.len() {
+            for s in 0..=light_vertices {
+                let depth = s as i64 + t as i64 - 2;
+                if (s == 1 && t == 1) || depth < 0 || depth > self.max_bounces as i64 {
+                    continue;
+                }
+
+                radiance = radiance + self.connect(scene, camera, &light_path, &camera_path, s, t, ray.time(), splats);
+            }
+        }
+
+        radiance
+    }
+}
+
+impl Integrator for Bidirectional {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        self.trace(ray, scene, None, &mut Vec::new(), sampler)
+    }
+
+    fn sample_radiance(&self, ray: &Ray, scene: &Scene, sample: &mut CameraSample) -> Vec3 {
+        self.trace(ray, scene, Some(sample.camera), sample.splats, sample.sampler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::integrators::testing::{average, close, floor_radiance, floor_ray, lit_floor};
+
+    #[test]
+    fn point_light_on_a_lambertian_floor() {
+        // Without a camera no light is splatted, so the radiance holds every strategy that counts
+        let scene = lit_floor();
+        let radiance = average(&Bidirectional::new(8), &floor_ray(0.5), &scene, 64);
+        assert!(close(radiance, floor_radiance(0.5), 1e-3));
+    }
+}
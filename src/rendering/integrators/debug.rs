@@ -0,0 +1,124 @@
+use crate::rendering::integrators::integrator::Integrator;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::bvh::count_box_tests;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+pub enum DebugMode {
+    Normals,
+    Depth { max_distance: f32 },
+    Uvs,
+    // Every object gets a color from its index in the scene, which stands in for its material
+    // since each object owns one. The colors stay the same across threads and runs.
+    MaterialIds,
+    // Bounding boxes tested to find the first hit, red at max_tests
+    BvhCost { max_tests: u32 },
+    // Length of a random path until it escapes or gets absorbed, red at the bounce limit
+    Bounces,
+}
+
+impl Clone for DebugMode {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for DebugMode {}
+
+// Blue at 0 over green to red at 1
+fn heatmap(value: f32) -> Vec3 {
+    let value = value.clamp(0.0, 1.0);
+    if value < 0.5 {
+        Vec3(0.0, 2.0 * value, 1.0 - 2.0 * value)
+    } else {
+        Vec3(2.0 * value - 1.0, 2.0 - 2.0 * value, 0.0)
+    }
+}
+
+fn material_color(id: u32) -> Vec3 {
+    let hash = (id as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    let channel = |shift: u32| ((hash >> shift) & 0xFF) as f32 / 255.0;
+    Vec3(channel(40), channel(48), channel(56))
+}
+
+// Visualizes properties of the first hit instead of light. The output is squared so the gamma
+// correction of the renderer doesn't distort it.
+pub struct DebugView {
+    mode: DebugMode,
+    max_bounces: u32,
+}
+
+impl DebugView {
+    pub fn new(mode: DebugMode, max_bounces: u32) -> Self {
+        Self { mode, max_bounces }
+    }
+
+    fn bounces(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> u32 {
+        let mut ray = Ray::new(ray.origin(), ray.direction(), ray.time());
+        for bounces in 0..self.max_bounces {
+            let mut hit_record = HitRecord::new();
+            if !scene.hit(&ray, 0.001, f32::INFINITY, &mut hit_record) {
+                return bounces;
+            }
+
+            match hit_record.material.unwrap().sample(&ray, &hit_record, sampler) {
+                Some(scatter) => ray = Ray::new(hit_record.position, scatter.direction, ray.time()),
+                None => return bounces + 1,
+            }
+        }
+        self.max_bounces
+    }
+}
+
+impl Integrator for DebugView {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut hit_record = HitRecord::new();
+        let (hit, box_tests) = count_box_tests(|| scene.hit(ray, 0.001, f32::INFINITY, &mut hit_record));
+
+        let color = match self.mode {
+            DebugMode::BvhCost { max_tests } => heatmap(box_tests as f32 / max_tests as f32),
+            DebugMode::Bounces => heatmap(self.bounces(ray, scene, sampler) as f32 / self.max_bounces.max(1) as f32),
+            _ if !hit => Vec3(0.0, 0.0, 0.0),
+            DebugMode::Normals => (hit_record.normal + 1.0) * 0.5,
+            DebugMode::Depth { max_distance } => {
+                let distance = hit_record.t * ray.direction().length();
+                let value = 1.0 - (distance / max_distance).min(1.0);
+                Vec3(value, value, value)
+            }
+            DebugMode::Uvs => Vec3(hit_record.u.rem_euclid(1.0), hit_record.v.rem_euclid(1.0), 0.0),
+            DebugMode::MaterialIds => material_color(hit_record.object_id),
+        };
+
+        color.mul(&color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::integrators::testing::{average, floor_ray, lit_floor};
+
+    #[test]
+    fn shows_normals_and_depth() {
+        let scene = lit_floor();
+        let normals = average(&DebugView::new(DebugMode::Normals, 8), &floor_ray(0.0), &scene, 1);
+        assert!((normals.0 - 0.25).abs() < 1e-6 && (normals.1 - 0.25).abs() < 1e-6 && (normals.2 - 1.0).abs() < 1e-6);
+
+        // The floor is 1.5 units below the ray origin
+        let depth = average(&DebugView::new(DebugMode::Depth { max_distance: 3.0 }, 8), &floor_ray(0.0), &scene, 1);
+        assert!((depth.0 - 0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn colors_objects_by_their_index() {
+        let scene = lit_floor();
+        let floor = average(&DebugView::new(DebugMode::MaterialIds, 8), &floor_ray(0.0), &scene, 1);
+        let ceiling_ray = Ray::new(Vec3(0.0, 0.0, 1.5), Vec3(0.0, 0.0, 1.0), 0.0);
+        let ceiling = average(&DebugView::new(DebugMode::MaterialIds, 8), &ceiling_ray, &scene, 1);
+        let first = material_color(0);
+        assert!(floor.0 == first.0 * first.0 && floor.1 == first.1 * first.1);
+        assert!(ceiling.0 != floor.0 || ceiling.1 != floor.1 || ceiling.2 != floor.2);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::rendering::integrators::integrator::Integrator;
+use crate::rendering::integrators::light_sampling::{bsdf_weight, hit_light, sample_direct_light};
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Only light that reaches the camera after at most one bounce, from the sky or emissive surfaces
+pub struct DirectLighting;
+
+impl Integrator for DirectLighting {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut hit_record = HitRecord::new();
+        if !scene.hit(ray, 0.001, f32::INFINITY, &mut hit_record) {
+            return scene.background(ray);
+        }
+
+        let material = hit_record.material.unwrap();
+        let emitted = material.emitted(&hit_record);
+        let direct = sample_direct_light(scene, ray, &hit_record, true);
+        let scatter = match material.sample(ray, &hit_record, sampler) {
+            Some(scatter) => scatter,
+            None => return emitted + direct,
+        };
+
+        let scattered = Ray::new(hit_record.position, scatter.direction, ray.time());
+        let mut light_record = HitRecord::new();
+        let incoming = if scene.hit(&scattered, 0.001, f32::INFINITY, &mut light_record) {
+            let emitted = light_record.material.unwrap().emitted(&light_record);
+            if emitted.near_zero() {
+                emitted
+            } else {
+                emitted * bsdf_weight(scene, &scattered, scatter.pdf, hit_light(scene, &scattered, &light_record))
+            }
+        } else {
+            scene.background(&scattered) * bsdf_weight(scene, &scattered, scatter.pdf, scene.environment())
+        };
+
+        emitted + direct + scatter.weight.mul(&incoming)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::integrators::testing::{average, close, floor_radiance, floor_ray, lit_floor};
+
+    #[test]
+    fn point_light_on_a_lambertian_floor() {
+        let scene = lit_floor();
+        for x in [0.0, 0.5, 2.0] {
+            let radiance = average(&DirectLighting, &floor_ray(x), &scene, 16);
+            assert!(close(radiance, floor_radiance(x), 1e-4));
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use crate::rendering::camera::Camera;
+use crate::rendering::integrators::ambient_occlusion::AmbientOcclusion;
+use crate::rendering::integrators::bidirectional::Bidirectional;
+use crate::rendering::integrators::debug::{DebugMode, DebugView};
+use crate::rendering::integrators::direct_lighting::DirectLighting;
+use crate::rendering::integrators::path_tracer::PathTracer;
+use crate::rendering::integrators::photon_mapping::{PhotonMapping, PhotonPasses};
+use crate::rendering::integrators::whitted::Whitted;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::vector_math::Vec3;
+use crate::rendering::volumes::fog::Fog;
+
+// Computes the light arriving along a camera ray. Every thread renders with its own instance.
+// Integrators may keep work that only depends on the scene between calls, but never anything that
+// depends on the rays they were asked about before.
+pub trait Integrator {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3;
+
+    // Integrators needing more than the ray get the whole camera sample
+    fn sample_radiance(&self, ray: &Ray, scene: &Scene, sample: &mut CameraSample) -> Vec3 {
+        self.radiance(ray, scene, sample.sampler)
+    }
+}
+
+// Light landing on the pixel at x, y. Like camera samples, splats are divided by the number of
+// samples per pixel.
+pub struct Splat {
+    pub x: u32,
+    pub y: u32,
+    pub radiance: Vec3,
+}
+
+// What the renderer knows about a camera ray. Index counts the samples of the pixel from zero,
+// the sampler continues with the dimensions the camera ray didn't use. Integrators following
+// light from the lights to the camera also find light for other pixels and leave it in splats,
+// which the renderer adds to the film.
+pub struct CameraSample<'a> {
+    pub camera: &'a Camera,
+    pub index: u32,
+    pub sampler: &'a mut dyn Sampler,
+    pub splats: &'a mut Vec<Splat>,
+}
+
+pub enum IntegratorKind {
+    PathTracer,
+    Whitted,
+    AmbientOcclusion { distance: f32 },
+    DirectLighting,
+    Bidirectional,
+    // Photons traced per pass and the lookup radius of the first pass
+    PhotonMapping { photons: u32, radius: f32 },
+    Debug(DebugMode),
+}
+
+impl IntegratorKind {
+    // Photon passes are shared by the instances of all threads
+    pub fn create(&self, max_bounces: u32, fog: Option<Fog>, photon_passes: &Arc<PhotonPasses>) -> Box<dyn Integrator> {
+        match *self {
+            IntegratorKind::PathTracer => Box::new(PathTracer::new(max_bounces, fog)),
+            IntegratorKind::Whitted => Box::new(Whitted::new(max_bounces)),
+            IntegratorKind::AmbientOcclusion { distance } => Box::new(AmbientOcclusion::new(distance)),
+            IntegratorKind::DirectLighting => Box::new(DirectLighting),
+            IntegratorKind::Bidirectional => Box::new(Bidirectional::new(max_bounces)),
+            IntegratorKind::PhotonMapping { photons, radius } => Box::new(PhotonMapping::new(max_bounces, photons, radius, Arc::clone(photon_passes))),
+            IntegratorKind::Debug(mode) => Box::new(DebugView::new(mode, max_bounces)),
+        }
+    }
+
+    // Photon mapping renders the whole image one sample at a time, so only one pass of photons
+    // has to be kept
+    pub fn renders_in_passes(&self) -> bool {
+        matches!(self, IntegratorKind::PhotonMapping { .. })
+    }
+}
+
+impl Clone for IntegratorKind {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for IntegratorKind {}
@@ -0,0 +1,161 @@
+use rand::Rng;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Weight of a sample taken with density pdf when another strategy could have produced it with
+// density other_pdf. Favours whichever strategy is more likely to find the sample.
+pub fn power_heuristic(pdf: f32, other_pdf: f32) -> f32 {
+    let square = pdf * pdf;
+    let other_square = other_pdf * other_pdf;
+    if square + other_square == 0.0 {
+        return 0.0;
+    }
+    square / (square + other_square)
+}
+
+// Light arriving at the hit directly from one randomly picked light of the scene, weighted by the
+// material. Picking one light with probability 1/n and dividing by it keeps the estimate unbiased.
+// With mis the sample is weighted against the material also finding the light on its own, in which
+// case the integrator has to weigh emission hit by scattered rays with bsdf_weight.
+pub fn sample_direct_light(scene: &Scene, ray: &Ray, hit_record: &HitRecord, mis: bool) -> Vec3 {
+    if scene.lights.is_empty() {
+        return Vec3(0.0, 0.0, 0.0);
+    }
+
+    let light = &scene.lights[random::rng().gen_range(0..scene.lights.len())];
+    let sample = match light.sample(hit_record.position) {
+        Some(sample) => sample,
+        None => return Vec3(0.0, 0.0, 0.0),
+    };
+
+    let material = hit_record.material.unwrap();
+    let factor = material.eval(ray, hit_record, sample.direction);
+    if factor.near_zero() {
+        return Vec3(0.0, 0.0, 0.0);
+    }
+
+    // Stop a bit in front of the light, so the light itself doesn't count as a blocker
+    let shadow_ray = Ray::new(hit_record.position, sample.direction, ray.time());
+    let mut blocker = HitRecord::new();
+    if scene.hit(&shadow_ray, 0.001, sample.distance * (1.0 - 1e-4), &mut blocker) {
+        return Vec3(0.0, 0.0, 0.0);
+    }
+
+    let light_pdf = sample.pdf / scene.lights.len() as f32;
+    // Rays scattered by the material never hit delta lights, so they are left to light sampling
+    let weight = if mis && !light.is_delta() {
+        power_heuristic(light_pdf, material.pdf(ray, hit_record, sample.direction))
+    } else {
+        1.0
+    };
+
+    factor.mul(&sample.radiance) * (weight / light_pdf)
+}
+
+// Weight of emission found by a ray the material scattered with density bsdf_pdf. Light is the
+// index of the light the ray found, the environment if it escaped. Emitters that aren't lights of
+// the scene and directions without a density, which came from a perfect mirror, can't be matched
+// by light sampling.
+pub fn bsdf_weight(scene: &Scene, ray: &Ray, bsdf_pdf: f32, light: Option<usize>) -> f32 {
+    let light = match light {
+        Some(light) if bsdf_pdf > 0.0 => light,
+        _ => return 1.0,
+    };
+    power_heuristic(bsdf_pdf, scene.light_pdf(light, ray.origin(), ray.direction()))
+}
+
+// Light of the scene at the hit, for weighing its emission with bsdf_weight
+pub fn hit_light(scene: &Scene, ray: &Ray, hit_record: &HitRecord) -> Option<usize> {
+    scene.light_at(hit_record.position, -1.0 * ray.direction())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use std::sync::Arc;
+    use super::*;
+    use crate::rendering::integrators::testing::plane;
+    use crate::rendering::lights::area_light::AreaLight;
+    use crate::rendering::lights::environment_light::{EnvironmentLight, EnvironmentMap};
+    use crate::rendering::materials::diffuse_light::DiffuseLight;
+    use crate::rendering::materials::lambertian::Lambertian;
+    use crate::rendering::samplers::independent::Independent;
+    use crate::rendering::shapes::bvh::Bvh;
+    use crate::rendering::shapes::hittable::Hittable;
+    use crate::rendering::shapes::quad::Quad;
+
+    const ALBEDO: f32 = 0.5;
+    const SKY: f32 = 0.5;
+
+    // Square panel light of side 4 one unit above a diffuse floor, under a uniform sky. Light
+    // sampling picks the panel or the sky.
+    fn scene() -> Scene {
+        let panel = Arc::new(Quad::new(Vec3(-2.0, -2.0, 1.0), Vec3(0.0, 4.0, 0.0), Vec3(4.0, 0.0, 0.0), Box::new(DiffuseLight::new(Vec3(1.0, 1.0, 1.0)))));
+        let objects: Vec<Box<dyn Hittable>> = vec![
+            plane(0.0, true, Box::new(Lambertian::new(Vec3(ALBEDO, ALBEDO, ALBEDO)))),
+            Box::new(Arc::clone(&panel)),
+        ];
+
+        let mut scene = Scene::new(Box::new(Bvh::new(objects)));
+        scene.add_light(Box::new(AreaLight::new(panel)));
+        let sky = EnvironmentMap::new(8, 4, vec![Vec3(SKY, SKY, SKY); 32]).unwrap();
+        scene.set_environment(Box::new(EnvironmentLight::new(Arc::new(sky))));
+        scene
+    }
+
+    // Light leaving the floor below the center of the panel. The panel covers the fraction of the
+    // cosine weighted hemisphere given by the form factor of a point to a parallel rectangle, the sky
+    // the rest.
+    fn expected() -> f32 {
+        let x: f32 = 2.0;
+        let root = (1.0 + x * x).sqrt();
+        let form_factor = 4.0 / PI * x / root * (x / root).atan();
+        ALBEDO * (form_factor + SKY * (1.0 - form_factor))
+    }
+
+    // Direct light leaving the floor towards the camera, found by sampling the lights, the material
+    // or both combined
+    fn direct_light(scene: &Scene, light_sampling: bool, bsdf_sampling: bool) -> f32 {
+        let ray = Ray::new(Vec3(0.0, 0.0, 0.5), Vec3(0.0, 0.0, -1.0), 0.0);
+        let mut hit_record = HitRecord::new();
+        assert!(scene.hit(&ray, 0.001, f32::INFINITY, &mut hit_record));
+        let material = hit_record.material.unwrap();
+
+        let samples = 100000;
+        let mut sum = 0.0;
+        for index in 0..samples {
+            random::start_sample(1, (0, 0), index);
+            if light_sampling {
+                sum += sample_direct_light(scene, &ray, &hit_record, bsdf_sampling).0;
+            }
+            if bsdf_sampling {
+                let scatter = material.sample(&ray, &hit_record, &mut Independent).unwrap();
+                let scattered = Ray::new(hit_record.position, scatter.direction, 0.0);
+                let mut light_record = HitRecord::new();
+                let (incoming, light) = if scene.hit(&scattered, 0.001, f32::INFINITY, &mut light_record) {
+                    (light_record.material.unwrap().emitted(&light_record), hit_light(scene, &scattered, &light_record))
+                } else {
+                    (scene.background(&scattered), scene.environment())
+                };
+                let weight = if light_sampling { bsdf_weight(scene, &scattered, scatter.pdf, light) } else { 1.0 };
+                sum += scatter.weight.0 * incoming.0 * weight;
+            }
+        }
+        sum / samples as f32
+    }
+
+    #[test]
+    fn strategies_agree_for_a_panel_under_the_sky() {
+        // Emission found by the material is weighted against the panel only, the sky behind it
+        // can't be reached by light sampling in that direction
+        let scene = scene();
+        let expected = expected();
+        for (light_sampling, bsdf_sampling) in [(true, false), (false, true), (true, true)] {
+            let estimate = direct_light(&scene, light_sampling, bsdf_sampling);
+            assert!((estimate - expected).abs() < 0.02 * expected);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+pub mod integrator;
+pub mod path_tracer;
+pub mod whitted;
+pub mod ambient_occlusion;
+pub mod direct_lighting;
+pub mod debug;
+pub mod light_sampling;
+pub mod bidirectional;
+pub mod photon_map;
+pub mod photon_mapping;
+#[cfg(test)]
+pub mod testing;
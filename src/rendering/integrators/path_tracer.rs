@@ -0,0 +1,141 @@
+use crate::rendering::integrators::integrator::Integrator;
+use crate::rendering::integrators::light_sampling::{bsdf_weight, hit_light, sample_direct_light};
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+use crate::rendering::volumes::fog::Fog;
+
+// Follows a single random path per camera ray, bouncing until the ray escapes or the bounce limit
+// is reached. Every hit also looks at one light directly, so small lights don't depend on being
+// hit by chance. Both ways of finding a light are combined with multiple importance sampling.
+pub struct PathTracer {
+    max_bounces: u32,
+    roulette_depth: u32,
+    fog: Option<Fog>,
+}
+
+impl PathTracer {
+    pub fn new(max_bounces: u32, fog: Option<Fog>) -> Self {
+        Self { max_bounces, roulette_depth: 3, fog }
+    }
+
+    // Number of bounces after which paths may be ended by russian roulette
+    pub fn set_roulette_depth(&mut self, roulette_depth: u32) {
+        self.roulette_depth = roulette_depth;
+    }
+
+    // Chance of continuing a path after a bounce. Paths that carry little light are ended more
+    // often, the survivors are divided by the chance to make up for the ended ones. None if the
+    // path ends here.
+    fn survival(&self, throughput: Vec3, bounces: u32, sampler: &mut dyn Sampler) -> Option<f32> {
+        if bounces < self.roulette_depth {
+            return Some(1.0);
+        }
+
+        let probability = throughput.0.max(throughput.1).max(throughput.2).min(1.0);
+        if probability <= 0.0 || sampler.get_1d() >= probability {
+            return None;
+        }
+        Some(probability)
+    }
+}
+
+impl Integrator for PathTracer {
+    // Walks the path in a loop instead of recursing, so long paths through glass don't need a stack
+    // frame per bounce. Throughput is the fraction of the light found at the current vertex that
+    // reaches the camera, bsdf_pdf the density the current ray was scattered with (zero for camera
+    // rays and perfect mirrors).
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        let mut radiance = Vec3(0.0, 0.0, 0.0);
+        let mut throughput = Vec3(1.0, 1.0, 1.0);
+        let mut ray = Ray::new(ray.origin(), ray.direction(), ray.time());
+        let mut bsdf_pdf = 0.0;
+
+        for bounces in 1..=self.max_bounces {
+            let mut hit_record = HitRecord::new();
+            let hit = scene.hit(&ray, 0.001, f32::INFINITY, &mut hit_record);
+
+            // The fog scatters the ray if it gets there before the next surface. Rays escaping to the
+            // sky are left alone, otherwise an endless fog would swallow all of the light.
+            if let (Some(fog), true) = (self.fog, hit) {
+                let length = ray.direction().length();
+                let fog_distance = fog.sample_distance();
+                if fog_distance < hit_record.t * length {
+                    throughput = throughput.mul(&fog.color());
+                    match self.survival(throughput, bounces, sampler) {
+                        Some(survival) => throughput = throughput / survival,
+                        None => break,
+                    }
+
+                    ray = Ray::new(ray.at(fog_distance / length), fog.sample_direction(ray.direction()), ray.time());
+                    bsdf_pdf = 0.0;
+                    continue;
+                }
+            }
+
+            if !hit {
+                let sky = scene.background(&ray) * bsdf_weight(scene, &ray, bsdf_pdf, scene.environment());
+                radiance = radiance + throughput.mul(&sky);
+                break;
+            }
+
+            let material = hit_record.material.unwrap();
+            let mut emitted = material.emitted(&hit_record);
+            if !emitted.near_zero() {
+                emitted = emitted * bsdf_weight(scene, &ray, bsdf_pdf, hit_light(scene, &ray, &hit_record));
+            }
+            let direct = sample_direct_light(scene, &ray, &hit_record, true);
+            radiance = radiance + throughput.mul(&(emitted + direct));
+
+            let scatter = match material.sample(&ray, &hit_record, sampler) {
+                Some(scatter) => scatter,
+                None => break,
+            };
+
+            throughput = throughput.mul(&scatter.weight);
+            match self.survival(throughput, bounces, sampler) {
+                Some(survival) => throughput = throughput / survival,
+                None => break,
+            }
+
+            ray = Ray::new(hit_record.position, scatter.direction, ray.time());
+            bsdf_pdf = scatter.pdf;
+        }
+
+        radiance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use crate::rendering::integrators::testing::{average, close, floor_radiance, floor_ray, lit_floor};
+    use crate::rendering::lights::environment_light::{EnvironmentLight, EnvironmentMap};
+    use crate::rendering::materials::lambertian::Lambertian;
+    use crate::rendering::shapes::bvh::Bvh;
+    use crate::rendering::shapes::hittable::Hittable;
+    use crate::rendering::shapes::sphere::Sphere;
+
+    #[test]
+    fn furnace() {
+        // A convex diffuse object under a uniform white sky reflects its albedo, whether the sky is
+        // found by light sampling or by the material
+        let objects: Vec<Box<dyn Hittable>> = vec![Box::new(Sphere::new(Vec3(0.0, 0.0, -3.0), 1.0, Box::new(Lambertian::new(Vec3(0.5, 0.5, 0.5)))))];
+        let mut scene = Scene::new(Box::new(Bvh::new(objects)));
+        let map = EnvironmentMap::new(8, 4, vec![Vec3(1.0, 1.0, 1.0); 32]).unwrap();
+        scene.set_environment(Box::new(EnvironmentLight::new(Arc::new(map))));
+
+        let ray = Ray::new(Vec3(0.0, 0.0, 0.0), Vec3(0.2, 0.1, -1.0), 0.0);
+        assert!(close(average(&PathTracer::new(8, None), &ray, &scene, 4000), 0.5, 0.02));
+    }
+
+    #[test]
+    fn point_light_on_a_lambertian_floor() {
+        let scene = lit_floor();
+        let radiance = average(&PathTracer::new(8, None), &floor_ray(0.5), &scene, 64);
+        assert!(close(radiance, floor_radiance(0.5), 1e-3));
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use crate::rendering::vector_math::Vec3;
+
+// Light arriving at a surface, travelling along direction and carrying power
+pub struct Photon {
+    pub position: Vec3,
+    pub direction: Vec3,
+    pub power: Vec3,
+}
+
+// Photons sorted into a hash grid. Cells are as wide as the lookup diameter, so a lookup only
+// has to visit the 2x2x2 cells around its position.
+pub struct PhotonMap {
+    photons: Vec<Photon>,
+    cells: HashMap<(i32, i32, i32), Vec<usize>>,
+    radius: f32,
+}
+
+impl PhotonMap {
+    pub fn new(photons: Vec<Photon>, radius: f32) -> Self {
+        let mut map = Self { photons, cells: HashMap::new(), radius };
+        for index in 0..map.photons.len() {
+            let cell = map.cell(map.photons[index].position);
+            map.cells.entry(cell).or_default().push(index);
+        }
+        map
+    }
+
+    fn cell(&self, position: Vec3) -> (i32, i32, i32) {
+        let size = 2.0 * self.radius;
+        ((position.0 / size).floor() as i32, (position.1 / size).floor() as i32, (position.2 / size).floor() as i32)
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    // Calls visit for every photon within the radius of position
+    pub fn for_each_near(&self, position: Vec3, mut visit: impl FnMut(&Photon)) {
+        let low = self.cell(position + -self.radius);
+        let high = self.cell(position + self.radius);
+        let radius_squared = self.radius * self.radius;
+
+        for x in low.0..=high.0 {
+            for y in low.1..=high.1 {
+                for z in low.2..=high.2 {
+                    for &index in self.cells.get(&(x, y, z)).into_iter().flatten() {
+                        let photon = &self.photons[index];
+                        if (photon.position - position).length_squared() <= radius_squared {
+                            visit(photon);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+use rand::Rng;
+use crate::rendering::integrators::integrator::{CameraSample, Integrator};
+use crate::rendering::integrators::light_sampling::sample_direct_light;
+use crate::rendering::integrators::photon_map::{Photon, PhotonMap};
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::independent::Independent;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// How much of the radius each pass keeps, between 0 and 1. Smaller values shrink it faster,
+// trading noise for less blur.
+const ALPHA: f32 = 2.0 / 3.0;
+
+// Bounces after which photons may be ended by russian roulette
+const ROULETTE_DEPTH: u32 = 3;
+
+// Stochastic progressive photon mapping (Knaus and Zwicker 2011). Every sample of a pixel looks
+// up a pass of photons traced from the lights, each pass with more and more photons per area: the
+// radius of the lookup shrinks from one pass to the next, just slow enough for the noise and the
+// blur to vanish together. That finds caustics through glass which no path from the camera can
+// connect to a light.
+//
+// Camera rays are followed through perfect mirrors and glass. Everywhere else the light sources
+// are sampled directly and the photons add the indirect light. An environment light sends out
+// photons like any other light, the plain sky doesn't and only shows up directly or after one
+// bounce.
+//
+// Passes are traced the first time a sample index needs them and are shared by all threads
+// rendering the image, which renders one pass after the other.
+pub struct PhotonMapping {
+    max_bounces: u32,
+    photons: u32,
+    radius: f32,
+    passes: Arc<PhotonPasses>,
+}
+
+// One pass of photons per sample of a pixel. Whichever thread needs a pass first traces it with its
+// own copy of the scene, the others wait for it. Only the latest pass is kept, it's dropped once
+// the threads still using it are done. The random numbers of a pass only depend on its index, so
+// it doesn't matter which thread traces it, nor how often.
+pub struct PhotonPasses {
+    passes: u32,
+    current: Mutex<Option<(usize, Arc<PhotonMap>)>>,
+}
+
+impl PhotonPasses {
+    pub fn new(samples: u32) -> Self {
+        Self { passes: samples.max(1), current: Mutex::new(None) }
+    }
+}
+
+impl PhotonMapping {
+    // The radius is that of the first pass, in scene units
+    pub fn new(max_bounces: u32, photons: u32, radius: f32, passes: Arc<PhotonPasses>) -> Self {
+        Self { max_bounces, photons, radius, passes }
+    }
+
+    // Traces photons starting at randomly picked lights, at random times while the shutter is open.
+    // Photons are only kept after their first bounce, direct light is sampled at the camera side.
+    fn trace_photons(&self, scene: &Scene, radius: f32, shutter: (f32, f32)) -> PhotonMap {
+        let mut photons = Vec::new();
+        if scene.lights.is_empty() {
+            return PhotonMap::new(photons, radius);
+        }
+
+        // Photons belong to no pixel, so they don't take their numbers from the pixel's sampler
+        let mut rng = random::rng();
+        let mut sampler = Independent;
+        for _ in 0..self.photons {
+            let index = rng.gen_range(0..scene.lights.len());
+            let emission = match scene.lights[index].sample_emission() {
+                Some(emission) => emission,
+                None => continue,
+            };
+
+            let pdf = emission.position_pdf * emission.direction_pdf / scene.lights.len() as f32;
+            if pdf <= 0.0 {
+                continue;
+            }
+
+            let cosine = (emission.normal * emission.direction).abs();
+            let emitted = emission.radiance * (cosine / (pdf * self.photons as f32));
+            let mut power = emitted;
+            let time = shutter.0 + (shutter.1 - shutter.0) * rng.gen::<f32>();
+            let mut ray = Ray::new(emission.position, emission.direction, time);
+
+            for bounces in 0..self.max_bounces {
+                let mut hit_record = HitRecord::new();
+                if !scene.hit(&ray, 0.001, f32::INFINITY, &mut hit_record) {
+                    break;
+                }
+
+                let material = hit_record.material.unwrap();
+                if bounces > 0 && !material.is_volume() {
+                    photons.push(Photon { position: hit_record.position, direction: ray.direction().unit(), power });
+                }
+
+                let scatter = match material.sample(&ray, &hit_record, &mut sampler) {
+                    Some(scatter) => scatter,
+                    None => break,
+                };
+
+                power = power.mul(&scatter.weight);
+
+                // Photons keeping little of their power are ended more often, the survivors carry
+                // the power of the ended ones
+                if bounces + 1 >= ROULETTE_DEPTH {
+                    let kept = power.0.max(power.1).max(power.2) / emitted.0.max(emitted.1).max(emitted.2);
+                    let survival = kept.min(1.0);
+                    if survival <= 0.0 || rng.gen::<f32>() >= survival {
+                        break;
+                    }
+                    power = power / survival;
+                }
+
+                ray = Ray::new(hit_record.position, scatter.direction, time);
+            }
+        }
+
+        PhotonMap::new(photons, radius)
+    }
+
+    // Traces the pass unless another thread did already
+    fn pass(&self, scene: &Scene, index: usize, shutter: (f32, f32)) -> Arc<PhotonMap> {
+        let index = index.min(self.passes.passes as usize - 1);
+        let mut current = self.passes.current.lock().unwrap();
+        if let Some((pass, map)) = current.as_ref() {
+            if *pass == index {
+                return Arc::clone(map);
+            }
+        }
+
+        let mut radius = self.radius;
+        for pass in 1..=index {
+            let pass = pass as f32;
+            radius *= ((pass + ALPHA) / (pass + 1.0)).sqrt();
+        }
+        let map = Arc::new(random::with_stream(&[index as u32], || self.trace_photons(scene, radius, shutter)));
+        *current = Some((index, Arc::clone(&map)));
+        map
+    }
+
+    // Light the photons bring to the hit, scattered towards the ray
+    fn gather(&self, map: &PhotonMap, ray: &Ray, hit_record: &HitRecord) -> Vec3 {
+        let material = hit_record.material.unwrap();
+        let mut sum = Vec3(0.0, 0.0, 0.0);
+
+        map.for_each_near(hit_record.position, |photon| {
+            let direction = -1.0 * photon.direction;
+            let cosine = (direction * hit_record.normal).abs();
+            if cosine > 1e-4 {
+                let factor = material.eval(ray, hit_record, direction) / cosine;
+                sum = sum + factor.mul(&photon.power);
+            }
+        });
+
+        sum / (PI * map.radius() * map.radius())
+    }
+
+    fn estimate(&self, ray: &Ray, scene: &Scene, index: usize, shutter: (f32, f32), sampler: &mut dyn Sampler) -> Vec3 {
+        let map = self.pass(scene, index, shutter);
+
+        let mut radiance = Vec3(0.0, 0.0, 0.0);
+        let mut throughput = Vec3(1.0, 1.0, 1.0);
+        let mut ray = Ray::new(ray.origin(), ray.direction(), ray.time());
+        // Emission is only counted if the lights weren't sampled at the previous hit already
+        let mut count_emitted = true;
+
+        for _ in 0..self.max_bounces {
+            let mut hit_record = HitRecord::new();
+            if !scene.hit(&ray, 0.001, f32::INFINITY, &mut hit_record) {
+                if count_emitted || scene.environment().is_none() {
+                    radiance = radiance + throughput.mul(&scene.background(&ray));
+                }
+                break;
+            }
+
+            let material = hit_record.material.unwrap();
+            if count_emitted {
+                radiance = radiance + throughput.mul(&material.emitted(&hit_record));
+            }
+
+            let direct = sample_direct_light(scene, &ray, &hit_record, false);
+            radiance = radiance + throughput.mul(&direct);
+
+            let scatter = match material.sample(&ray, &hit_record, sampler) {
+                Some(scatter) => scatter,
+                None => break,
+            };
+            let scattered = Ray::new(hit_record.position, scatter.direction, ray.time());
+
+            // Media have no surface to collect photons on, the path goes on through them instead
+            if material.is_volume() {
+                throughput = throughput.mul(&scatter.weight);
+                ray = scattered;
+                count_emitted = false;
+                continue;
+            }
+
+            radiance = radiance + throughput.mul(&self.gather(&map, &ray, &hit_record));
+
+            if !scatter.is_specular() {
+                // Photons bring everything but the plain sky, which only gets this one bounce
+                let mut sky_record = HitRecord::new();
+                if scene.environment().is_none() && !scene.hit(&scattered, 0.001, f32::INFINITY, &mut sky_record) {
+                    radiance = radiance + throughput.mul(&scatter.weight).mul(&scene.background(&scattered));
+                }
+                break;
+            }
+
+            throughput = throughput.mul(&scatter.weight);
+            ray = scattered;
+            count_emitted = true;
+        }
+
+        radiance
+    }
+}
+
+impl Integrator for PhotonMapping {
+    // Without the index of the sample every ray looks up the first pass, and without the camera
+    // the photons see the scene at the time of the ray
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        self.estimate(ray, scene, 0, (ray.time(), ray.time()), sampler)
+    }
+
+    fn sample_radiance(&self, ray: &Ray, scene: &Scene, sample: &mut CameraSample) -> Vec3 {
+        self.estimate(ray, scene, sample.index as usize, sample.camera.shutter(), sample.sampler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::integrators::testing::{average, close, floor_radiance, floor_ray, lit_floor};
+
+    #[test]
+    fn point_light_on_a_lambertian_floor() {
+        // Photons bouncing off the floor end on the black ceiling, so only the direct light is left
+        let scene = lit_floor();
+        let integrator = PhotonMapping::new(8, 1000, 0.1, Arc::new(PhotonPasses::new(1)));
+        let radiance = average(&integrator, &floor_ray(0.5), &scene, 16);
+        assert!(close(radiance, floor_radiance(0.5), 1e-3));
+    }
+
+    #[test]
+    fn keeps_only_the_latest_pass() {
+        let scene = lit_floor();
+        let passes = Arc::new(PhotonPasses::new(4));
+        let first = PhotonMapping::new(8, 100, 0.1, Arc::clone(&passes));
+        let second = PhotonMapping::new(8, 100, 0.1, Arc::clone(&passes));
+
+        let map = first.pass(&scene, 0, (0.0, 1.0));
+        assert!(Arc::ptr_eq(&map, &second.pass(&scene, 0, (0.0, 1.0))));
+
+        let dropped = Arc::downgrade(&map);
+        drop(map);
+        let next = second.pass(&scene, 1, (0.0, 1.0));
+        assert!(dropped.upgrade().is_none());
+        assert!(Arc::ptr_eq(&next, &first.pass(&scene, 1, (0.0, 1.0))));
+    }
+}
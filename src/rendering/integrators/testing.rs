@@ -0,0 +1,65 @@
+use std::f32::consts::PI;
+use crate::rendering::integrators::integrator::Integrator;
+use crate::rendering::lights::point_light::PointLight;
+use crate::rendering::materials::lambertian::Lambertian;
+use crate::rendering::materials::material::Material;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::independent::Independent;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::bvh::Bvh;
+use crate::rendering::shapes::hittable::Hittable;
+use crate::rendering::shapes::quad::Quad;
+use crate::rendering::vector_math::Vec3;
+
+pub const ALBEDO: f32 = 0.5;
+pub const INTENSITY: f32 = 4.0;
+
+// Large quad in the plane z = height, facing up or down
+pub fn plane(height: f32, up: bool, material: Box<dyn Material>) -> Box<dyn Hittable> {
+    let (u, v) = (Vec3(1000.0, 0.0, 0.0), Vec3(0.0, 1000.0, 0.0));
+    let corner = Vec3(-500.0, -500.0, height);
+    if up {
+        Box::new(Quad::new(corner, u, v, material))
+    } else {
+        Box::new(Quad::new(corner, v, u, material))
+    }
+}
+
+// Lambertian floor at z = 0 under a point light at (0, 0, 1). A black ceiling at z = 2 keeps the
+// sky out, so the floor only gets the light of the point light.
+pub fn lit_floor() -> Scene {
+    let objects = vec![
+        plane(0.0, true, Box::new(Lambertian::new(Vec3(ALBEDO, ALBEDO, ALBEDO)))),
+        plane(2.0, false, Box::new(Lambertian::new(Vec3(0.0, 0.0, 0.0)))),
+    ];
+    let mut scene = Scene::new(Box::new(Bvh::new(objects)));
+    scene.add_light(Box::new(PointLight::new(Vec3(0.0, 0.0, 1.0), Vec3(INTENSITY, INTENSITY, INTENSITY))));
+    scene
+}
+
+// Camera ray hitting the floor of lit_floor at (x, 0, 0)
+pub fn floor_ray(x: f32) -> Ray {
+    Ray::new(Vec3(0.0, 0.0, 1.5), Vec3(x, 0.0, -1.5), 0.0)
+}
+
+// Radiance leaving the floor of lit_floor at (x, 0, 0)
+pub fn floor_radiance(x: f32) -> f32 {
+    let distance_squared = x * x + 1.0;
+    let cosine = 1.0 / distance_squared.sqrt();
+    ALBEDO / PI * INTENSITY * cosine / distance_squared
+}
+
+pub fn close(a: Vec3, value: f32, tolerance: f32) -> bool {
+    (a.0 - value).abs() < tolerance && (a.1 - value).abs() < tolerance && (a.2 - value).abs() < tolerance
+}
+
+// Mean of the radiance over the given number of samples, each with its own random numbers
+pub fn average(integrator: &dyn Integrator, ray: &Ray, scene: &Scene, samples: u32) -> Vec3 {
+    let mut sum = Vec3(0.0, 0.0, 0.0);
+    for index in 0..samples {
+        random::start_sample(1, (0, 0), index);
+        sum = sum + integrator.radiance(ray, scene, &mut Independent);
+    }
+    sum / samples as f32
+}
@@ -0,0 +1,104 @@
+use crate::rendering::integrators::integrator::Integrator;
+use crate::rendering::integrators::light_sampling::sample_direct_light;
+use crate::rendering::materials::material::Lobe;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Classic recursive ray tracing: specular materials are followed, everything else is shaded by
+// the lights of the scene and the sky above its normal as long as nothing blocks the view of it
+pub struct Whitted {
+    max_bounces: u32,
+}
+
+impl Whitted {
+    pub fn new(max_bounces: u32) -> Self {
+        Self { max_bounces }
+    }
+
+    fn trace(&self, ray: &Ray, scene: &Scene, depth: u32, sampler: &mut dyn Sampler) -> Vec3 {
+        if depth == 0 {
+            return Vec3(0.0, 0.0, 0.0);
+        }
+
+        let mut hit_record = HitRecord::new();
+        if !scene.hit(ray, 0.001, f32::INFINITY, &mut hit_record) {
+            return scene.background(ray);
+        }
+
+        let material = hit_record.material.unwrap();
+        let emitted = material.emitted(&hit_record);
+        let scatter = match material.sample(ray, &hit_record, sampler) {
+            Some(scatter) => scatter,
+            None => return emitted,
+        };
+
+        if scatter.lobe == Lobe::Specular {
+            let scattered = Ray::new(hit_record.position, scatter.direction, ray.time());
+            return emitted + scatter.weight.mul(&self.trace(&scattered, scene, depth - 1, sampler));
+        }
+
+        if scatter.lobe == Lobe::Glossy {
+            // Follow the mirror direction without any fuzz
+            let reflected = Vec3::reflect(&ray.direction().unit(), &hit_record.normal);
+            let reflected = Ray::new(hit_record.position, reflected, ray.time());
+            return emitted + scatter.weight.mul(&self.trace(&reflected, scene, depth - 1, sampler));
+        }
+
+        let direct = sample_direct_light(scene, ray, &hit_record, false);
+
+        // An environment light is part of the lights sampled above already
+        if scene.environment().is_some() {
+            return emitted + direct;
+        }
+
+        let to_sky = Ray::new(hit_record.position, hit_record.normal, ray.time());
+        let mut blocker = HitRecord::new();
+        if scene.hit(&to_sky, 0.001, f32::INFINITY, &mut blocker) {
+            return emitted + direct;
+        }
+
+        emitted + direct + scatter.weight.mul(&scene.background(&to_sky))
+    }
+}
+
+impl Integrator for Whitted {
+    fn radiance(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Vec3 {
+        self.trace(ray, scene, self.max_bounces, sampler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::integrators::testing::{average, close, floor_radiance, floor_ray, lit_floor, plane};
+    use crate::rendering::materials::diffuse_light::DiffuseLight;
+    use crate::rendering::materials::metal::Metal;
+    use crate::rendering::samplers::independent::Independent;
+    use crate::rendering::shapes::bvh::Bvh;
+
+    #[test]
+    fn reflects_off_a_perfect_mirror() {
+        // The mirror shows the light panel above it, darkened by its color
+        let objects = vec![
+            plane(0.0, true, Box::new(Metal::new(Vec3(0.8, 0.6, 0.4), 0.0))),
+            plane(1.0, false, Box::new(DiffuseLight::new(Vec3(2.0, 2.0, 2.0)))),
+        ];
+        let scene = Scene::new(Box::new(Bvh::new(objects)));
+
+        let ray = Ray::new(Vec3(0.0, 0.0, 0.5), Vec3(1.0, 0.0, -1.0), 0.0);
+        let radiance = Whitted::new(4).radiance(&ray, &scene, &mut Independent);
+        assert!((radiance.0 - 1.6).abs() < 1e-5);
+        assert!((radiance.1 - 1.2).abs() < 1e-5);
+        assert!((radiance.2 - 0.8).abs() < 1e-5);
+    }
+
+    #[test]
+    fn shades_diffuse_surfaces_with_the_lights() {
+        let scene = lit_floor();
+        let radiance = average(&Whitted::new(4), &floor_ray(0.5), &scene, 1);
+        assert!(close(radiance, floor_radiance(0.5), 1e-4));
+    }
+}
@@ -0,0 +1,116 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+use rand::Rng;
+use crate::rendering::lights::light::{EmissionSample, Light, LightSample};
+use crate::rendering::onb::Onb;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+fn emitted(record: &HitRecord) -> Vec3 {
+    record.material.map_or(Vec3(0.0, 0.0, 0.0), |material| material.emitted(record))
+}
+
+// Samples an emissive shape. The same shape has to be added to the world as well, otherwise the
+// light shines without being visible or casting shadows. Quads and disks with a two sided
+// DiffuseLight make rectangular and round panels lighting both of their sides.
+pub struct AreaLight {
+    shape: Arc<dyn Hittable>,
+}
+
+impl AreaLight {
+    pub fn new(shape: Arc<dyn Hittable>) -> Self {
+        Self { shape }
+    }
+
+    // Finds the surface at position by shooting a short ray back at it from the side direction
+    // points to. The record faces direction, so it's on the front if light leaves that way.
+    fn surface_at(&self, position: Vec3, direction: Vec3) -> Option<HitRecord<'_>> {
+        let mut record = HitRecord::new();
+        let probe = Ray::new(position + direction * 1e-3, -1.0 * direction, 0.0);
+        if !self.shape.hit(&probe, 0.0, 2e-3, &mut record) {
+            return None;
+        }
+        Some(record)
+    }
+
+    // Records of the sides of the surface at position that emit light, with what they emit
+    fn emitting_sides(&self, position: Vec3, normal: Vec3) -> Vec<(HitRecord<'_>, Vec3)> {
+        [normal, -1.0 * normal].into_iter()
+            .filter_map(|side| self.surface_at(position, side))
+            .map(|record| {
+                let radiance = emitted(&record);
+                (record, radiance)
+            })
+            .filter(|(_, radiance)| !radiance.near_zero())
+            .collect()
+    }
+}
+
+impl Light for AreaLight {
+    fn sample(&self, position: Vec3) -> Option<LightSample> {
+        let direction = self.shape.random_direction(position).unit();
+
+        let mut record = HitRecord::new();
+        if !self.shape.hit(&Ray::new(position, direction, 0.0), 0.001, f32::INFINITY, &mut record) {
+            return None;
+        }
+
+        let pdf = self.shape.pdf_value(position, direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let radiance = emitted(&record);
+
+        Some(LightSample { direction, distance: record.t, normal: record.normal, radiance, pdf })
+    }
+
+    fn pdf(&self, position: Vec3, direction: Vec3) -> f32 {
+        self.shape.pdf_value(position, direction)
+    }
+
+    // Uniform over the area and cosine weighted around the normal of a side that emits. Two sided
+    // surfaces pick either side half of the time.
+    fn sample_emission(&self) -> Option<EmissionSample> {
+        let area = self.shape.area();
+        let (position, normal) = self.shape.sample_surface()?;
+        if area <= 0.0 {
+            return None;
+        }
+
+        let mut sides = self.emitting_sides(position, normal);
+        if sides.is_empty() {
+            return None;
+        }
+        let count = sides.len();
+        let (record, radiance) = sides.swap_remove(random::rng().gen_range(0..count));
+
+        let local = Vec3::random_cosine_direction();
+        let direction = Onb::new(record.normal).local(local);
+
+        Some(EmissionSample {
+            position: record.position,
+            normal: record.normal,
+            direction,
+            radiance,
+            position_pdf: 1.0 / area,
+            direction_pdf: local.2 / (PI * count as f32),
+        })
+    }
+
+    fn emission_pdf(&self, position: Vec3, direction: Vec3) -> (f32, f32) {
+        let area = self.shape.area();
+        match self.surface_at(position, direction) {
+            Some(record) if area > 0.0 => {
+                let count = self.emitting_sides(position, record.normal).len();
+                if emitted(&record).near_zero() || count == 0 {
+                    return (1.0 / area, 0.0);
+                }
+                (1.0 / area, (record.normal * direction).max(0.0) / (PI * count as f32))
+            }
+            _ => (0.0, 0.0),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::rendering::lights::light::{EmissionSample, Light, LightSample, SceneBounds};
+use crate::rendering::vector_math::Vec3;
+
+// Parallel light from infinitely far away like the sun, travelling along the direction. The
+// irradiance is what arrives on a surface facing the light.
+pub struct DirectionalLight {
+    direction: Vec3,
+    irradiance: Vec3,
+    bounds: SceneBounds,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vec3, irradiance: Vec3) -> Self {
+        Self { direction: direction.unit(), irradiance, bounds: SceneBounds { center: Vec3(0.0, 0.0, 0.0), radius: 0.0 } }
+    }
+}
+
+impl Light for DirectionalLight {
+    // Without scene bounds the light is infinitely far away, otherwise on the disk paths start on
+    fn sample(&self, position: Vec3) -> Option<LightSample> {
+        let towards = -1.0 * self.direction;
+        let distance = self.bounds.distance(position, towards);
+
+        Some(LightSample { direction: towards, distance, normal: self.direction, radiance: self.irradiance, pdf: 1.0 })
+    }
+
+    fn pdf(&self, _position: Vec3, _direction: Vec3) -> f32 {
+        0.0
+    }
+
+    fn sample_emission(&self) -> Option<EmissionSample> {
+        if self.bounds.radius <= 0.0 {
+            return None;
+        }
+
+        Some(EmissionSample {
+            position: self.bounds.disk_point(-1.0 * self.direction),
+            normal: self.direction,
+            direction: self.direction,
+            radiance: self.irradiance,
+            position_pdf: self.bounds.disk_pdf(),
+            direction_pdf: 1.0,
+        })
+    }
+
+    fn emission_pdf(&self, _position: Vec3, _direction: Vec3) -> (f32, f32) {
+        (self.bounds.disk_pdf(), 1.0)
+    }
+
+    fn is_delta(&self) -> bool {
+        true
+    }
+
+    fn is_infinite(&self) -> bool {
+        true
+    }
+
+    fn set_scene_bounds(&mut self, bounds: SceneBounds) {
+        self.bounds = bounds;
+    }
+}
@@ -0,0 +1,177 @@
+use std::f32::consts::PI;
+use std::path::Path;
+use std::sync::Arc;
+use rand::Rng;
+use crate::rendering::distribution::Distribution2D;
+use crate::rendering::lights::light::{EmissionSample, Light, LightSample, SceneBounds};
+use crate::rendering::random;
+use crate::rendering::vector_math::Vec3;
+
+fn luminance(color: Vec3) -> f32 {
+    0.2126 * color.0 + 0.7152 * color.1 + 0.0722 * color.2
+}
+
+// Panorama of the light arriving from all directions, stored as an equirectangular image with +y
+// at the top row. Directions are sampled by the luminance of the pixels, so the sun in an outdoor
+// shot gets most of the samples.
+pub struct EnvironmentMap {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec3>,
+    distribution: Distribution2D,
+    rotation: f32,
+    intensity: f32,
+}
+
+impl EnvironmentMap {
+    // Pixels are given row by row, starting at the top
+    pub fn new(width: usize, height: usize, pixels: Vec<Vec3>) -> Result<Self, String> {
+        if width == 0 || height == 0 || pixels.len() != width * height {
+            return Err(format!("environment map of {}x{} pixels needs {} of them, got {}", width, height, width * height, pixels.len()));
+        }
+
+        // Rows near the poles cover less of the sphere than the ones at the horizon
+        let mut weights = Vec::with_capacity(pixels.len());
+        for y in 0..height {
+            let sin_theta = (PI * (y as f32 + 0.5) / height as f32).sin();
+            weights.extend(pixels[y * width..(y + 1) * width].iter().map(|&pixel| luminance(pixel).max(0.0) * sin_theta));
+        }
+        let distribution = Distribution2D::new(&weights, width, height);
+
+        Ok(Self { width, height, pixels, distribution, rotation: 0.0, intensity: 1.0 })
+    }
+
+    // HDR and EXR files hold linear radiance, which is used as it is
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let image = image::open(path).map_err(|e| e.to_string())?.to_rgb32f();
+        let (width, height) = image.dimensions();
+        let pixels = image.pixels().map(|pixel| Vec3(pixel[0], pixel[1], pixel[2])).collect();
+
+        Self::new(width as usize, height as usize, pixels)
+    }
+
+    // Turns the panorama counterclockwise around the up axis, by the angle in radians
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+
+    fn rotate(&self, direction: Vec3, angle: f32) -> Vec3 {
+        let (sin, cos) = angle.sin_cos();
+        Vec3(direction.0 * cos + direction.2 * sin, direction.1, direction.2 * cos - direction.0 * sin)
+    }
+
+    // Position of the direction in the image, both between 0 and 1
+    fn image_position(&self, direction: Vec3) -> (f32, f32) {
+        let direction = self.rotate(direction.unit(), -self.rotation);
+        let theta = direction.1.clamp(-1.0, 1.0).acos();
+        let phi = (-direction.2).atan2(direction.0) + PI;
+
+        ((phi / (2.0 * PI)).clamp(0.0, 1.0), (theta / PI).clamp(0.0, 1.0))
+    }
+
+    fn direction_at(&self, x: f32, y: f32) -> Vec3 {
+        let theta = PI * y;
+        let phi = 2.0 * PI * x - PI;
+        let direction = Vec3(phi.cos() * theta.sin(), theta.cos(), -phi.sin() * theta.sin());
+        self.rotate(direction, self.rotation)
+    }
+
+    // Light arriving from the direction
+    pub fn radiance(&self, direction: Vec3) -> Vec3 {
+        let (x, y) = self.image_position(direction);
+        let column = ((x * self.width as f32) as usize).min(self.width - 1);
+        let row = ((y * self.height as f32) as usize).min(self.height - 1);
+
+        self.pixels[row * self.width + column] * self.intensity
+    }
+
+    // Density over solid angle of sample picking the direction
+    pub fn pdf(&self, direction: Vec3) -> f32 {
+        let (x, y) = self.image_position(direction);
+        let sin_theta = (PI * y).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(x, y) / (2.0 * PI * PI * sin_theta)
+    }
+
+    // Direction towards a bright part of the panorama, with its density over solid angle
+    pub fn sample(&self) -> Option<(Vec3, f32)> {
+        let mut rng = random::rng();
+        let ((x, y), pdf) = self.distribution.sample((rng.gen(), rng.gen()));
+        let sin_theta = (PI * y).sin();
+        if pdf <= 0.0 || sin_theta <= 0.0 {
+            return None;
+        }
+
+        Some((self.direction_at(x, y), pdf / (2.0 * PI * PI * sin_theta)))
+    }
+}
+
+// Lights the scene with an environment map, which is shared between all threads rendering it
+pub struct EnvironmentLight {
+    map: Arc<EnvironmentMap>,
+    bounds: SceneBounds,
+}
+
+impl EnvironmentLight {
+    pub fn new(map: Arc<EnvironmentMap>) -> Self {
+        Self { map, bounds: SceneBounds { center: Vec3(0.0, 0.0, 0.0), radius: 0.0 } }
+    }
+}
+
+impl Light for EnvironmentLight {
+    fn sample(&self, position: Vec3) -> Option<LightSample> {
+        let (direction, pdf) = self.map.sample()?;
+
+        Some(LightSample {
+            direction,
+            distance: self.bounds.distance(position, direction),
+            normal: -1.0 * direction,
+            radiance: self.map.radiance(direction),
+            pdf,
+        })
+    }
+
+    fn pdf(&self, _position: Vec3, direction: Vec3) -> f32 {
+        self.map.pdf(direction)
+    }
+
+    // Picks the direction the light comes from, then a point on the disk of the scene bounds
+    fn sample_emission(&self) -> Option<EmissionSample> {
+        if self.bounds.radius <= 0.0 {
+            return None;
+        }
+
+        let (towards, direction_pdf) = self.map.sample()?;
+
+        Some(EmissionSample {
+            position: self.bounds.disk_point(towards),
+            normal: -1.0 * towards,
+            direction: -1.0 * towards,
+            radiance: self.map.radiance(towards),
+            position_pdf: self.bounds.disk_pdf(),
+            direction_pdf,
+        })
+    }
+
+    fn emission_pdf(&self, _position: Vec3, direction: Vec3) -> (f32, f32) {
+        (self.bounds.disk_pdf(), self.map.pdf(-1.0 * direction))
+    }
+
+    fn radiance(&self, direction: Vec3) -> Vec3 {
+        self.map.radiance(direction)
+    }
+
+    fn is_infinite(&self) -> bool {
+        true
+    }
+
+    fn set_scene_bounds(&mut self, bounds: SceneBounds) {
+        self.bounds = bounds;
+    }
+}
@@ -0,0 +1,103 @@
+use std::f32::consts::PI;
+use rand::Rng;
+use crate::rendering::onb::Onb;
+use crate::rendering::random;
+use crate::rendering::vector_math::Vec3;
+
+// Direction towards a point on a light together with what it emits and how likely it was picked.
+// The pdf is over solid angle as seen from the shaded position.
+pub struct LightSample {
+    pub direction: Vec3,
+    pub distance: f32,
+    pub normal: Vec3,
+    pub radiance: Vec3,
+    pub pdf: f32,
+}
+
+// Ray leaving a light. The position is picked with a density over the area of the light, the
+// direction with one over solid angle. Lights without an area or a spread of directions report a
+// density of one for the position or direction they always pick.
+pub struct EmissionSample {
+    pub position: Vec3,
+    pub normal: Vec3,
+    pub direction: Vec3,
+    pub radiance: Vec3,
+    pub position_pdf: f32,
+    pub direction_pdf: f32,
+}
+
+// Sphere around everything in the scene. Infinite lights start their paths on a disk as large as
+// the sphere, touching it on the side the light comes from.
+pub struct SceneBounds {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl SceneBounds {
+    // Distance from position to the disk of light arriving from towards
+    pub fn distance(&self, position: Vec3, towards: Vec3) -> f32 {
+        if self.radius > 0.0 {
+            ((self.center - position) * towards + self.radius).max(1e-3)
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    // Uniformly distributed point on the disk of light arriving from towards
+    pub fn disk_point(&self, towards: Vec3) -> Vec3 {
+        let mut rng = random::rng();
+        let radius = self.radius * rng.gen::<f32>().sqrt();
+        let phi = 2.0 * PI * rng.gen::<f32>();
+        self.center + towards * self.radius + Onb::new(towards).local(Vec3(phi.cos() * radius, phi.sin() * radius, 0.0))
+    }
+
+    // Density of disk_point over the area of the disk
+    pub fn disk_pdf(&self) -> f32 {
+        if self.radius > 0.0 {
+            1.0 / (PI * self.radius * self.radius)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Clone for SceneBounds {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for SceneBounds {}
+
+// Light source the integrators can aim rays at directly instead of waiting to hit it by chance
+pub trait Light {
+    fn sample(&self, position: Vec3) -> Option<LightSample>;
+
+    fn pdf(&self, position: Vec3, direction: Vec3) -> f32;
+
+    // Starts a path on the light, for integrators following the light towards the camera
+    fn sample_emission(&self) -> Option<EmissionSample>;
+
+    // Position and direction densities of sample_emission leaving the light at position along
+    // direction. Both are zero for positions that don't lie on the light.
+    fn emission_pdf(&self, position: Vec3, direction: Vec3) -> (f32, f32);
+
+    // Lights in a single point or shining in a single direction. Rays can't hit them, so only
+    // sampling the light finds them.
+    fn is_delta(&self) -> bool {
+        false
+    }
+
+    // Lights infinitely far away. Their paths start on the disk of the scene bounds.
+    fn is_infinite(&self) -> bool {
+        false
+    }
+
+    // Light of an infinite light arriving along direction, seen by rays leaving the scene that way
+    fn radiance(&self, _direction: Vec3) -> Vec3 {
+        Vec3(0.0, 0.0, 0.0)
+    }
+
+    // Passed in when the light is added to the scene
+    fn set_scene_bounds(&mut self, _bounds: SceneBounds) {}
+}
@@ -0,0 +1,56 @@
+use std::f32::consts::PI;
+use crate::rendering::lights::light::{EmissionSample, Light, LightSample};
+use crate::rendering::vector_math::Vec3;
+
+// Shines equally in all directions from a single point. The intensity is the radiant intensity,
+// the light reaching a surface falls off with the square of its distance.
+pub struct PointLight {
+    position: Vec3,
+    intensity: Vec3,
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, intensity: Vec3) -> Self {
+        Self { position, intensity }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, position: Vec3) -> Option<LightSample> {
+        let offset = self.position - position;
+        let distance = offset.length();
+        if distance <= 0.0 {
+            return None;
+        }
+
+        let direction = offset / distance;
+        let radiance = self.intensity / (distance * distance);
+
+        Some(LightSample { direction, distance, normal: -1.0 * direction, radiance, pdf: 1.0 })
+    }
+
+    fn pdf(&self, _position: Vec3, _direction: Vec3) -> f32 {
+        0.0
+    }
+
+    fn sample_emission(&self) -> Option<EmissionSample> {
+        let direction = Vec3::random_unit_vector();
+
+        Some(EmissionSample {
+            position: self.position,
+            normal: direction,
+            direction,
+            radiance: self.intensity,
+            position_pdf: 1.0,
+            direction_pdf: 1.0 / (4.0 * PI),
+        })
+    }
+
+    fn emission_pdf(&self, _position: Vec3, _direction: Vec3) -> (f32, f32) {
+        (1.0, 1.0 / (4.0 * PI))
+    }
+
+    fn is_delta(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,263 @@
+use std::f32::consts::PI;
+use rand::Rng;
+use crate::rendering::lights::environment_light::EnvironmentMap;
+use crate::rendering::lights::light::{EmissionSample, Light, LightSample, SceneBounds};
+use crate::rendering::onb::Onb;
+use crate::rendering::random;
+use crate::rendering::vector_math::Vec3;
+
+// Angle from the center of the sun to its edge as seen from the earth
+const SUN_RADIUS: f32 = 0.00465;
+
+// Luminance of the sun before its light passes through the atmosphere, in kcd/m^2 like the sky
+const SUN_LUMINANCE: f32 = 2.0e6;
+
+// Resolution of the panorama the sky is sampled with
+const SAMPLING_WIDTH: usize = 128;
+const SAMPLING_HEIGHT: usize = 64;
+
+fn luminance(color: Vec3) -> f32 {
+    0.2126 * color.0 + 0.7152 * color.1 + 0.0722 * color.2
+}
+
+// Distribution of the sky brightness by the angle theta to the zenith and gamma to the sun
+// (Perez et al. 1993)
+fn perez(coefficients: &[f32; 5], cos_theta: f32, gamma: f32) -> f32 {
+    let [a, b, c, d, e] = *coefficients;
+    (1.0 + a * (b / cos_theta.max(0.01)).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos() * gamma.cos())
+}
+
+fn xyy_to_rgb(x: f32, y: f32, luminance: f32) -> Vec3 {
+    if y <= 0.0 {
+        return Vec3(0.0, 0.0, 0.0);
+    }
+
+    let big_x = x / y * luminance;
+    let big_z = (1.0 - x - y) / y * luminance;
+    Vec3(
+        (3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z).max(0.0),
+        (-0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z).max(0.0),
+        (0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z).max(0.0),
+    )
+}
+
+// Clear daylight sky after Preetham, Shirley and Smits (1999). The sun stands at the elevation above
+// the horizon and the azimuth counterclockwise from -z when seen from above, both in radians.
+// Turbidity is the haziness of the air, from about 2 on a very clear day to 10 in thick haze.
+//
+// Radiance is in kcd/m^2 scaled by the intensity, which by default keeps a sunlit white surface
+// somewhat below one. Below the horizon the sky is black, scenes bring their own ground.
+pub struct Sky {
+    sun_direction: Vec3,
+    turbidity: f32,
+    intensity: f32,
+    coefficients: [[f32; 5]; 3],
+    zenith: [f32; 3],
+}
+
+impl Sky {
+    pub fn new(elevation: f32, azimuth: f32, turbidity: f32) -> Self {
+        let elevation = elevation.clamp(0.0, PI / 2.0);
+        let sun_direction = Vec3(-elevation.cos() * azimuth.sin(), elevation.sin(), -elevation.cos() * azimuth.cos());
+        let t = turbidity.clamp(1.7, 10.0);
+
+        // Coefficients of the distribution of the luminance and the two chromaticities
+        let coefficients = [
+            [0.1787 * t - 1.4630, -0.3554 * t + 0.4275, -0.0227 * t + 5.3251, 0.1206 * t - 2.5771, -0.0670 * t + 0.3703],
+            [-0.0193 * t - 0.2592, -0.0665 * t + 0.0008, -0.0004 * t + 0.2125, -0.0641 * t - 0.8989, -0.0033 * t + 0.0452],
+            [-0.0167 * t - 0.2608, -0.0950 * t + 0.0092, -0.0079 * t + 0.2102, -0.0441 * t - 1.6537, -0.0109 * t + 0.0529],
+        ];
+
+        // Values at the zenith the distributions are scaled to
+        let theta = PI / 2.0 - elevation;
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta);
+        let cubic = |c: [f32; 4]| ((c[0] * theta + c[1]) * theta + c[2]) * theta + c[3];
+        let zenith = [
+            ((4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192).max(0.0),
+            t * t * cubic([0.00166, -0.00375, 0.00209, 0.0]) + t * cubic([-0.02903, 0.06377, -0.03202, 0.00394]) + cubic([0.11693, -0.21196, 0.06052, 0.25886]),
+            t * t * cubic([0.00275, -0.00610, 0.00317, 0.0]) + t * cubic([-0.04214, 0.08970, -0.04153, 0.00516]) + cubic([0.15346, -0.26756, 0.06670, 0.26688]),
+        ];
+
+        Self { sun_direction, turbidity: t, intensity: 0.02, coefficients, zenith }
+    }
+
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    // Light of the sky arriving from the direction, without the sun
+    pub fn radiance(&self, direction: Vec3) -> Vec3 {
+        let direction = direction.unit();
+        if direction.1 <= 0.0 {
+            return Vec3(0.0, 0.0, 0.0);
+        }
+
+        let gamma = (direction * self.sun_direction).clamp(-1.0, 1.0).acos();
+        let theta_sun = self.sun_direction.1.clamp(-1.0, 1.0).acos();
+        let value = |channel: usize| {
+            let coefficients = &self.coefficients[channel];
+            self.zenith[channel] * perez(coefficients, direction.1, gamma) / perez(coefficients, 1.0, theta_sun)
+        };
+
+        xyy_to_rgb(value(1), value(2), value(0)) * self.intensity
+    }
+
+    // Light of the sun after its way through the atmosphere, which reddens and dims it towards the
+    // horizon (from the appendix of Preetham et al.)
+    pub fn sun_radiance(&self) -> Vec3 {
+        let theta = self.sun_direction.1.clamp(0.0, 1.0).acos();
+        let relative_mass = 1.0 / (theta.cos() + 0.15 * (93.885 - theta.to_degrees()).powf(-1.253));
+        let beta = 0.04608 * self.turbidity - 0.04586;
+
+        let transmittance = |wavelength: f32| {
+            let rayleigh = (-0.008735 * wavelength.powf(-4.08) * relative_mass).exp();
+            let aerosol = (-beta * wavelength.powf(-1.3) * relative_mass).exp();
+            rayleigh * aerosol
+        };
+
+        // Wavelengths in micrometers standing in for red, green and blue
+        Vec3(transmittance(0.65), transmittance(0.55), transmittance(0.45)) * (SUN_LUMINANCE * self.intensity)
+    }
+}
+
+impl Clone for Sky {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for Sky {}
+
+// The sky and the sun disk in it as one infinite light. Directions are sampled from either, picked
+// by how much light each of them brings.
+pub struct SkyLight {
+    sky: Sky,
+    sun: Onb,
+    sun_radiance: Vec3,
+    cos_sun: f32,
+    sun_probability: f32,
+    sampling: EnvironmentMap,
+    bounds: SceneBounds,
+}
+
+impl SkyLight {
+    pub fn new(sky: Sky) -> Self {
+        // The sky changes slowly enough to be sampled from a coarse panorama of itself, its
+        // radiance is still looked up exactly
+        let mut pixels = Vec::with_capacity(SAMPLING_WIDTH * SAMPLING_HEIGHT);
+        let mut sky_power = 0.0;
+        for y in 0..SAMPLING_HEIGHT {
+            let theta = PI * (y as f32 + 0.5) / SAMPLING_HEIGHT as f32;
+            for x in 0..SAMPLING_WIDTH {
+                let phi = 2.0 * PI * (x as f32 + 0.5) / SAMPLING_WIDTH as f32 - PI;
+                let direction = Vec3(phi.cos() * theta.sin(), theta.cos(), -phi.sin() * theta.sin());
+                let radiance = sky.radiance(direction);
+                sky_power += luminance(radiance) * theta.sin();
+                pixels.push(radiance);
+            }
+        }
+        sky_power *= 2.0 * PI * PI / (SAMPLING_WIDTH * SAMPLING_HEIGHT) as f32;
+        let sampling = EnvironmentMap::new(SAMPLING_WIDTH, SAMPLING_HEIGHT, pixels).expect("sky panorama has the size it was made with");
+
+        let sun_radiance = sky.sun_radiance();
+        let sun_power = luminance(sun_radiance) * 2.0 * PI * (1.0 - SUN_RADIUS.cos());
+        let sun_probability = if sun_power + sky_power > 0.0 { sun_power / (sun_power + sky_power) } else { 0.0 };
+
+        Self {
+            sky,
+            sun: Onb::new(sky.sun_direction()),
+            sun_radiance,
+            cos_sun: SUN_RADIUS.cos(),
+            sun_probability,
+            sampling,
+            bounds: SceneBounds { center: Vec3(0.0, 0.0, 0.0), radius: 0.0 },
+        }
+    }
+
+    fn sun_pdf(&self) -> f32 {
+        1.0 / (2.0 * PI * (1.0 - self.cos_sun))
+    }
+
+    fn sample_direction(&self) -> Option<Vec3> {
+        let mut rng = random::rng();
+        if rng.gen::<f32>() >= self.sun_probability {
+            return self.sampling.sample().map(|(direction, _)| direction);
+        }
+
+        let cos_theta = 1.0 - rng.gen::<f32>() * (1.0 - self.cos_sun);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f32>();
+        Some(self.sun.local(Vec3(phi.cos() * sin_theta, phi.sin() * sin_theta, cos_theta)))
+    }
+
+    // Density of sample_direction over solid angle, with both ways of picking the direction
+    fn direction_pdf(&self, direction: Vec3) -> f32 {
+        let direction = direction.unit();
+        let sun = if direction * self.sun.w >= self.cos_sun { self.sun_pdf() } else { 0.0 };
+        self.sun_probability * sun + (1.0 - self.sun_probability) * self.sampling.pdf(direction)
+    }
+}
+
+impl Light for SkyLight {
+    fn sample(&self, position: Vec3) -> Option<LightSample> {
+        let direction = self.sample_direction()?;
+        let pdf = self.direction_pdf(direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance: self.bounds.distance(position, direction),
+            normal: -1.0 * direction,
+            radiance: self.radiance(direction),
+            pdf,
+        })
+    }
+
+    fn pdf(&self, _position: Vec3, direction: Vec3) -> f32 {
+        self.direction_pdf(direction)
+    }
+
+    fn sample_emission(&self) -> Option<EmissionSample> {
+        if self.bounds.radius <= 0.0 {
+            return None;
+        }
+
+        let towards = self.sample_direction()?;
+
+        Some(EmissionSample {
+            position: self.bounds.disk_point(towards),
+            normal: -1.0 * towards,
+            direction: -1.0 * towards,
+            radiance: self.radiance(towards),
+            position_pdf: self.bounds.disk_pdf(),
+            direction_pdf: self.direction_pdf(towards),
+        })
+    }
+
+    fn emission_pdf(&self, _position: Vec3, direction: Vec3) -> (f32, f32) {
+        (self.bounds.disk_pdf(), self.direction_pdf(-1.0 * direction))
+    }
+
+    fn radiance(&self, direction: Vec3) -> Vec3 {
+        let sky = self.sky.radiance(direction);
+        if direction.unit() * self.sun.w >= self.cos_sun && self.sun.w.1 > 0.0 {
+            sky + self.sun_radiance
+        } else {
+            sky
+        }
+    }
+
+    fn is_infinite(&self) -> bool {
+        true
+    }
+
+    fn set_scene_bounds(&mut self, bounds: SceneBounds) {
+        self.bounds = bounds;
+    }
+}
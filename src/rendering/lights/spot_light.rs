@@ -0,0 +1,102 @@
+use std::f32::consts::PI;
+use rand::Rng;
+use crate::rendering::lights::light::{EmissionSample, Light, LightSample};
+use crate::rendering::onb::Onb;
+use crate::rendering::random;
+use crate::rendering::vector_math::Vec3;
+
+// Point light shining into a cone around its direction. The angle is measured from the axis to the
+// edge of the cone in radians. Towards the edge the light fades out smoothly, over the outer blend
+// fraction of the angle.
+pub struct SpotLight {
+    position: Vec3,
+    onb: Onb,
+    intensity: Vec3,
+    cos_outer: f32,
+    cos_inner: f32,
+    angle: f32,
+}
+
+impl SpotLight {
+    pub fn new(position: Vec3, direction: Vec3, intensity: Vec3, angle: f32) -> Self {
+        let angle = angle.clamp(1e-3, PI / 2.0);
+        let mut light = Self { position, onb: Onb::new(direction), intensity, cos_outer: 0.0, cos_inner: 0.0, angle };
+        light.set_blend(0.15);
+        light
+    }
+
+    // Zero gives a hard edge, one fades out all the way from the axis
+    pub fn set_blend(&mut self, blend: f32) {
+        self.cos_outer = self.angle.cos();
+        self.cos_inner = (self.angle * (1.0 - blend.clamp(0.0, 1.0))).cos();
+    }
+
+    // Share of the intensity sent along a direction with the cosine to the axis
+    fn falloff(&self, cosine: f32) -> f32 {
+        if cosine <= self.cos_outer {
+            return 0.0;
+        }
+        if cosine >= self.cos_inner {
+            return 1.0;
+        }
+
+        let t = (cosine - self.cos_outer) / (self.cos_inner - self.cos_outer);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    fn cone_pdf(&self) -> f32 {
+        1.0 / (2.0 * PI * (1.0 - self.cos_outer))
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, position: Vec3) -> Option<LightSample> {
+        let offset = self.position - position;
+        let distance = offset.length();
+        if distance <= 0.0 {
+            return None;
+        }
+
+        let direction = offset / distance;
+        let falloff = self.falloff(-1.0 * direction * self.onb.w);
+        if falloff <= 0.0 {
+            return None;
+        }
+        let radiance = self.intensity * (falloff / (distance * distance));
+
+        Some(LightSample { direction, distance, normal: -1.0 * direction, radiance, pdf: 1.0 })
+    }
+
+    fn pdf(&self, _position: Vec3, _direction: Vec3) -> f32 {
+        0.0
+    }
+
+    // Uniform over the cone
+    fn sample_emission(&self) -> Option<EmissionSample> {
+        let mut rng = random::rng();
+        let cos_theta = 1.0 - rng.gen::<f32>() * (1.0 - self.cos_outer);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f32>();
+        let direction = self.onb.local(Vec3(phi.cos() * sin_theta, phi.sin() * sin_theta, cos_theta));
+
+        Some(EmissionSample {
+            position: self.position,
+            normal: direction,
+            direction,
+            radiance: self.intensity * self.falloff(cos_theta),
+            position_pdf: 1.0,
+            direction_pdf: self.cone_pdf(),
+        })
+    }
+
+    fn emission_pdf(&self, _position: Vec3, direction: Vec3) -> (f32, f32) {
+        if direction.unit() * self.onb.w <= self.cos_outer {
+            return (1.0, 0.0);
+        }
+        (1.0, self.cone_pdf())
+    }
+
+    fn is_delta(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,150 @@
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::materials::microfacet::{fresnel_dielectric, Ggx};
+use crate::rendering::materials::thin_film::thin_film_reflectance;
+use crate::rendering::onb::Onb;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Clear dielectric layer on top of any other material, like the lacquer of car paint. Light the
+// coat doesn't reflect reaches the base on the way in and has to pass the coat again on the way
+// out. Refraction inside the coat is ignored, the base sees the directions outside of it.
+pub struct Coated {
+    base: Box<dyn Material>,
+    ior: f32,
+    distribution: Ggx,
+    absorption: Vec3,
+    thin_film: Option<(f32, f32)>,
+}
+
+impl Coated {
+    pub fn new(base: Box<dyn Material>, ior: f32, roughness: f32) -> Self {
+        Self { base, ior, distribution: Ggx::new(roughness), absorption: Vec3(1.0, 1.0, 1.0), thin_film: None }
+    }
+
+    // Color of the coat when looking straight through it once. Grazing paths through the coat are
+    // longer and get tinted more.
+    pub fn set_absorption(&mut self, absorption: Vec3) {
+        self.absorption = absorption;
+    }
+
+    // Thin film of the given thickness in nanometers on top of the coat, which makes its reflection
+    // iridescent
+    pub fn set_thin_film(&mut self, thickness: f32, ior: f32) {
+        self.thin_film = Some((thickness, ior));
+    }
+
+    fn fresnel(&self, cosine: f32) -> Vec3 {
+        match self.thin_film {
+            Some((thickness, film_ior)) => thin_film_reflectance(cosine, thickness, film_ior, 1.0, self.ior),
+            None => {
+                let reflectance = fresnel_dielectric(cosine, self.ior);
+                Vec3(reflectance, reflectance, reflectance)
+            }
+        }
+    }
+
+    fn transmittance(&self, cos_o: f32, cos_i: f32) -> Vec3 {
+        let length = 1.0 / cos_o.max(1e-4) + 1.0 / cos_i.abs().max(1e-4);
+        let one = Vec3(1.0, 1.0, 1.0);
+        let absorption = Vec3(self.absorption.0.powf(length), self.absorption.1.powf(length), self.absorption.2.powf(length));
+
+        (one - self.fresnel(cos_o)).mul(&(one - self.fresnel(cos_i.abs()))).mul(&absorption)
+    }
+
+    // Chance of sampling the coat instead of the base
+    fn coat_probability(&self, cos_o: f32) -> f32 {
+        let reflectance = self.fresnel(cos_o);
+        ((reflectance.0 + reflectance.1 + reflectance.2) / 3.0).clamp(0.05, 0.95)
+    }
+
+    fn local(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> (Vec3, Vec3) {
+        let onb = Onb::new(hit_record.normal);
+        (onb.to_local(-1.0 * ray.direction().unit()), onb.to_local(direction.unit()))
+    }
+
+    // Reflection of the rough coat with its pdf, zero if the coat is smooth
+    fn coat(&self, wo: Vec3, wi: Vec3) -> (Vec3, f32) {
+        if self.distribution.is_smooth() || wo.2 <= 0.0 || wi.2 <= 0.0 {
+            return (Vec3(0.0, 0.0, 0.0), 0.0);
+        }
+
+        let m = (wo + wi).unit();
+        let d = self.distribution.distribution(m);
+        let g = self.distribution.masking_shadowing(wo, wi);
+        let value = self.fresnel(wo * m) * (d * g / (4.0 * wo.2));
+        let pdf = self.distribution.visible_pdf(wo, m) / (4.0 * (wo * m));
+
+        (value, pdf)
+    }
+}
+
+impl Material for Coated {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        if wo.2 <= 0.0 {
+            return self.base.sample(ray, hit_record, sampler);
+        }
+
+        let probability = self.coat_probability(wo.2);
+        if sampler.get_1d() < probability {
+            if self.distribution.is_smooth() {
+                let reflected = Vec3(-wo.0, -wo.1, wo.2);
+                return Some(ScatterRecord::new(onb.local(reflected), self.fresnel(wo.2) / probability, 0.0, Lobe::Specular));
+            }
+
+            let (u1, u2) = sampler.get_2d();
+            let m = self.distribution.sample_visible(wo, u1, u2);
+            let direction = onb.local(-1.0 * wo + m * (2.0 * (wo * m)));
+            let pdf = self.pdf(ray, hit_record, direction);
+            if pdf <= 0.0 {
+                return None;
+            }
+
+            return Some(ScatterRecord::new(direction, self.eval(ray, hit_record, direction) / pdf, pdf, Lobe::Glossy));
+        }
+
+        let sample = self.base.sample(ray, hit_record, sampler)?;
+        let wi = onb.to_local(sample.direction.unit());
+        let transmittance = self.transmittance(wo.2, wi.2);
+
+        // Without a rough coat the base is the only lobe that can find this direction
+        if sample.is_specular() || self.distribution.is_smooth() {
+            let pdf = sample.pdf * (1.0 - probability);
+            return Some(ScatterRecord::new(sample.direction, sample.weight.mul(&transmittance) / (1.0 - probability), pdf, sample.lobe));
+        }
+
+        let pdf = self.pdf(ray, hit_record, sample.direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord::new(sample.direction, self.eval(ray, hit_record, sample.direction) / pdf, pdf, sample.lobe))
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Vec3 {
+        self.base.emitted(hit_record)
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        let (wo, wi) = self.local(ray, hit_record, direction);
+        if wo.2 <= 0.0 {
+            return self.base.eval(ray, hit_record, direction);
+        }
+
+        let base = self.base.eval(ray, hit_record, direction).mul(&self.transmittance(wo.2, wi.2));
+        self.coat(wo, wi).0 + base
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        let (wo, wi) = self.local(ray, hit_record, direction);
+        if wo.2 <= 0.0 {
+            return self.base.pdf(ray, hit_record, direction);
+        }
+
+        let probability = self.coat_probability(wo.2);
+        probability * self.coat(wo, wi).1 + (1.0 - probability) * self.base.pdf(ray, hit_record, direction)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::materials::microfacet::{fresnel_conductor, Ggx};
+use crate::rendering::onb::Onb;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Measured complex indices of refraction at the wavelengths of red, green and blue
+pub enum MetalPreset {
+    Gold,
+    Silver,
+    Copper,
+    Aluminium,
+    Iron,
+    Chromium,
+}
+
+impl MetalPreset {
+    pub fn ior(&self) -> (Vec3, Vec3) {
+        match self {
+            MetalPreset::Gold => (Vec3(0.143, 0.374, 1.442), Vec3(3.983, 2.385, 1.603)),
+            MetalPreset::Silver => (Vec3(0.155, 0.117, 0.138), Vec3(4.828, 3.122, 2.147)),
+            MetalPreset::Copper => (Vec3(0.200, 0.924, 1.102), Vec3(3.912, 2.452, 2.142)),
+            MetalPreset::Aluminium => (Vec3(1.657, 0.880, 0.521), Vec3(9.224, 6.270, 4.837)),
+            MetalPreset::Iron => (Vec3(2.911, 2.950, 2.585), Vec3(3.089, 2.932, 2.767)),
+            MetalPreset::Chromium => (Vec3(3.107, 3.181, 2.323), Vec3(3.331, 3.329, 3.135)),
+        }
+    }
+}
+
+// Rough metal with a GGX microfacet distribution. The color comes from the complex index of
+// refraction instead of a tint, so it matches measured references.
+pub struct Conductor {
+    eta: Vec3,
+    k: Vec3,
+    distribution: Ggx,
+}
+
+impl Conductor {
+    pub fn new(eta: Vec3, k: Vec3, roughness: f32) -> Self {
+        Self { eta, k, distribution: Ggx::new(roughness) }
+    }
+
+    pub fn preset(preset: MetalPreset, roughness: f32) -> Self {
+        let (eta, k) = preset.ior();
+        Conductor::new(eta, k, roughness)
+    }
+
+    fn local_directions(ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> (Onb, Vec3, Vec3) {
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        let wi = onb.to_local(direction.unit());
+        (onb, wo, wi)
+    }
+}
+
+impl Material for Conductor {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        if self.distribution.is_smooth() {
+            let direction = Vec3::reflect(&ray.direction().unit(), &hit_record.normal);
+            let cosine = direction * hit_record.normal;
+            return Some(ScatterRecord::new(direction, fresnel_conductor(cosine, self.eta, self.k), 0.0, Lobe::Specular));
+        }
+
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        if wo.2 <= 0.0 {
+            return None;
+        }
+
+        let (u1, u2) = sampler.get_2d();
+        let m = self.distribution.sample_visible(wo, u1, u2);
+        let wi = -1.0 * wo + m * (2.0 * (wo * m));
+        if wi.2 <= 0.0 {
+            return None;
+        }
+
+        // The distribution terms cancel against the pdf of visible normals
+        let fresnel = fresnel_conductor(wo * m, self.eta, self.k);
+        let weight = fresnel * (self.distribution.masking_shadowing(wo, wi) / self.distribution.masking(wo));
+        let direction = onb.local(wi);
+        let pdf = self.pdf(ray, hit_record, direction);
+
+        Some(ScatterRecord::new(direction, weight, pdf, Lobe::Glossy))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        if self.distribution.is_smooth() {
+            return Vec3(0.0, 0.0, 0.0);
+        }
+
+        let (_, wo, wi) = Conductor::local_directions(ray, hit_record, direction);
+        if wo.2 <= 0.0 || wi.2 <= 0.0 {
+            return Vec3(0.0, 0.0, 0.0);
+        }
+
+        let m = (wo + wi).unit();
+        let fresnel = fresnel_conductor(wo * m, self.eta, self.k);
+        let d = self.distribution.distribution(m);
+        let g = self.distribution.masking_shadowing(wo, wi);
+
+        fresnel * (d * g / (4.0 * wo.2))
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+
+        let (_, wo, wi) = Conductor::local_directions(ray, hit_record, direction);
+        if wo.2 <= 0.0 || wi.2 <= 0.0 {
+            return 0.0;
+        }
+
+        let m = (wo + wi).unit();
+        self.distribution.visible_pdf(wo, m) / (4.0 * (wo * m))
+    }
+}
@@ -0,0 +1,145 @@
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::materials::microfacet::{fresnel_dielectric, refract, Ggx};
+use crate::rendering::onb::Onb;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Glass-like material that reflects and refracts according to the Fresnel equations. With a
+// roughness above zero the surface is made of GGX microfacets and looks frosted.
+pub struct Dielectric {
+    ior: f32,
+    distribution: Ggx,
+}
+
+impl Dielectric {
+    pub fn new(ior: f32, roughness: f32) -> Self {
+        Self { ior, distribution: Ggx::new(roughness) }
+    }
+
+    // Relative index of refraction, depending on whether the ray enters or leaves the material
+    fn eta(&self, hit_record: &HitRecord) -> f32 {
+        if hit_record.font_face { self.ior } else { 1.0 / self.ior }
+    }
+
+    // Microfacet normal connecting a pair of directions and whether it reflects, None if no
+    // microfacet facing both of them can connect them
+    fn half_vector(&self, wo: Vec3, wi: Vec3, eta: f32) -> Option<(Vec3, bool)> {
+        if wo.2 <= 0.0 || wi.2 == 0.0 {
+            return None;
+        }
+
+        let reflection = wi.2 > 0.0;
+        let m = if reflection { wo + wi } else { wo + wi * eta };
+        if m.near_zero() {
+            return None;
+        }
+        let m = if m.2 < 0.0 { -1.0 * m.unit() } else { m.unit() };
+
+        // Both directions have to lie on the expected sides of the microfacet
+        if wo * m <= 0.0 || (wi * m > 0.0) != reflection {
+            return None;
+        }
+
+        Some((m, reflection))
+    }
+
+    fn evaluate(&self, wo: Vec3, wi: Vec3, eta: f32) -> (f32, f32) {
+        let (m, reflection) = match self.half_vector(wo, wi, eta) {
+            Some(half_vector) => half_vector,
+            None => return (0.0, 0.0),
+        };
+
+        let fresnel = fresnel_dielectric(wo * m, eta);
+        let d = self.distribution.distribution(m);
+        let g = self.distribution.masking_shadowing(wo, wi);
+        let visible = self.distribution.visible_pdf(wo, m);
+
+        if reflection {
+            let value = fresnel * d * g / (4.0 * wo.2);
+            let pdf = fresnel * visible / (4.0 * (wo * m));
+            (value, pdf)
+        } else {
+            let denominator = wi * m + (wo * m) / eta;
+            let jacobian = (wi * m).abs() / (denominator * denominator);
+            let value = (1.0 - fresnel) * d * g * jacobian * (wo * m) / wo.2;
+            let pdf = (1.0 - fresnel) * visible * jacobian;
+            (value, pdf)
+        }
+    }
+}
+
+impl Material for Dielectric {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let eta = self.eta(hit_record);
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        if wo.2 <= 0.0 {
+            return None;
+        }
+
+        let choice = sampler.get_1d();
+        let (u1, u2) = sampler.get_2d();
+
+        // Pick reflection or refraction by the fresnel term, so the weight stays at one
+        if self.distribution.is_smooth() {
+            let normal = Vec3(0.0, 0.0, 1.0);
+            let wi = match refract(wo, normal, eta) {
+                Some(refracted) if choice >= fresnel_dielectric(wo.2, eta) => refracted,
+                _ => Vec3(-wo.0, -wo.1, wo.2),
+            };
+            return Some(ScatterRecord::new(onb.local(wi), Vec3(1.0, 1.0, 1.0), 0.0, Lobe::Specular));
+        }
+
+        let m = self.distribution.sample_visible(wo, u1, u2);
+        let wi = match refract(wo, m, eta) {
+            Some(refracted) if choice >= fresnel_dielectric(wo * m, eta) => {
+                if refracted.2 >= 0.0 {
+                    return None;
+                }
+                refracted
+            }
+            _ => {
+                let reflected = -1.0 * wo + m * (2.0 * (wo * m));
+                if reflected.2 <= 0.0 {
+                    return None;
+                }
+                reflected
+            }
+        };
+
+        let (value, pdf) = self.evaluate(wo, wi, eta);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let weight = value / pdf;
+        Some(ScatterRecord::new(onb.local(wi), Vec3(weight, weight, weight), pdf, Lobe::Glossy))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        if self.distribution.is_smooth() {
+            return Vec3(0.0, 0.0, 0.0);
+        }
+
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        let wi = onb.to_local(direction.unit());
+        let (value, _) = self.evaluate(wo, wi, self.eta(hit_record));
+
+        Vec3(value, value, value)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        let wi = onb.to_local(direction.unit());
+
+        self.evaluate(wo, wi, self.eta(hit_record)).1
+    }
+}
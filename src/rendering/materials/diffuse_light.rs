@@ -0,0 +1,36 @@
+use crate::rendering::materials::material::{Material, ScatterRecord};
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Emits light from the front side of a surface without reflecting anything
+pub struct DiffuseLight {
+    emit: Vec3,
+    two_sided: bool,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Vec3) -> Self {
+        Self { emit, two_sided: false }
+    }
+
+    // Emits from the back side as well, like a panel hanging freely in the room
+    pub fn set_two_sided(&mut self, two_sided: bool) {
+        self.two_sided = two_sided;
+    }
+}
+
+impl Material for DiffuseLight {
+    fn sample(&self, _ray: &Ray, _hit_record: &HitRecord, _sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Vec3 {
+        if hit_record.font_face || self.two_sided {
+            self.emit
+        } else {
+            Vec3(0.0, 0.0, 0.0)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::f32::consts::PI;
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::onb::Onb;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Phase function for media that prefer scattering forwards (g > 0) or backwards (g < 0)
+pub struct HenyeyGreenstein {
+    color: Vec3,
+    g: f32,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(color: Vec3, g: f32) -> Self {
+        Self { color, g: g.clamp(-0.99, 0.99) }
+    }
+}
+
+// Density of scattering by the angle between the incoming and outgoing direction
+pub fn henyey_greenstein(cos_theta: f32, g: f32) -> f32 {
+    let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+    (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+}
+
+pub fn sample_henyey_greenstein(direction: Vec3, g: f32, sample: (f32, f32)) -> Vec3 {
+    let xi = sample.0;
+
+    let cos_theta = if g.abs() < 1e-3 {
+        1.0 - 2.0 * xi
+    } else {
+        let square = (1.0 - g * g) / (1.0 + g - 2.0 * g * xi);
+        (1.0 + g * g - square * square) / (2.0 * g)
+    };
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi = 2.0 * PI * sample.1;
+
+    Onb::new(direction).local(Vec3(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta))
+}
+
+impl Material for HenyeyGreenstein {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let direction = sample_henyey_greenstein(ray.direction(), self.g, sampler.get_2d());
+        let pdf = self.pdf(ray, hit_record, direction);
+        Some(ScatterRecord::new(direction, self.color, pdf, Lobe::Glossy))
+    }
+
+    fn eval(&self, ray: &Ray, _hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        self.color * henyey_greenstein(ray.direction().unit() * direction.unit(), self.g)
+    }
+
+    fn pdf(&self, ray: &Ray, _hit_record: &HitRecord, direction: Vec3) -> f32 {
+        henyey_greenstein(ray.direction().unit() * direction.unit(), self.g)
+    }
+
+    fn is_volume(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,35 @@
+use std::f32::consts::PI;
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+// Phase function that scatters light equally in all directions
+pub struct Isotropic {
+    color: Vec3,
+}
+
+impl Isotropic {
+    pub fn new(color: Vec3) -> Self {
+        Self { color }
+    }
+}
+
+impl Material for Isotropic {
+    fn sample(&self, _ray: &Ray, _hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        Some(ScatterRecord::new(Vec3::sample_unit_vector(sampler.get_2d()), self.color, 1.0 / (4.0 * PI), Lobe::Diffuse))
+    }
+
+    fn eval(&self, _ray: &Ray, _hit_record: &HitRecord, _direction: Vec3) -> Vec3 {
+        self.color / (4.0 * PI)
+    }
+
+    fn pdf(&self, _ray: &Ray, _hit_record: &HitRecord, _direction: Vec3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn is_volume(&self) -> bool {
+        true
+    }
+}
@@ -1,5 +1,7 @@
-use crate::rendering::materials::material::Material;
+use std::f32::consts::PI;
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
 use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
 use crate::rendering::shapes::hittable::HitRecord;
 use crate::rendering::vector_math::Vec3;
 
@@ -20,16 +22,27 @@ impl Clone for Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, ray: &Ray, hit_record: &mut HitRecord) -> (bool, Ray, Vec3) {
-        let mut scatter_direction = hit_record.normal + Vec3::random_unit_vector();
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let mut scatter_direction = hit_record.normal + Vec3::sample_unit_vector(sampler.get_2d());
 
         if scatter_direction.near_zero() {
             scatter_direction = hit_record.normal;
         }
 
-        let scattered = Ray::new(hit_record.position, scatter_direction);
-        let attenuation = self.color;
+        // Cosine weighted, so the cosine and pi of eval cancel with the pdf
+        let pdf = self.pdf(ray, hit_record, scatter_direction);
+        Some(ScatterRecord::new(scatter_direction, self.color, pdf, Lobe::Diffuse))
+    }
+
+    fn eval(&self, _ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        let cosine = hit_record.normal * direction.unit();
+        if cosine <= 0.0 {
+            return Vec3(0.0, 0.0, 0.0);
+        }
+        self.color * (cosine / PI)
+    }
 
-        (true, scattered, attenuation)
+    fn pdf(&self, _ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        (hit_record.normal * direction.unit()).max(0.0) / PI
     }
 }
\ No newline at end of file
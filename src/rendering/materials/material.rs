@@ -1,7 +1,70 @@
 use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
 use crate::rendering::shapes::hittable::HitRecord;
 use crate::rendering::vector_math::Vec3;
 
+pub enum Lobe {
+    Diffuse,
+    Glossy,
+    // A single direction, which no other sampling strategy can ever find
+    Specular,
+}
+
+impl Clone for Lobe {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for Lobe {}
+
+impl PartialEq for Lobe {
+    fn eq(&self, other: &Self) -> bool {
+        matches!((self, other), (Lobe::Diffuse, Lobe::Diffuse) | (Lobe::Glossy, Lobe::Glossy) | (Lobe::Specular, Lobe::Specular))
+    }
+}
+
+// Direction picked by a material. The weight is eval divided by pdf, so integrators can multiply
+// it onto the path directly. Specular lobes have a pdf of zero.
+pub struct ScatterRecord {
+    pub direction: Vec3,
+    pub weight: Vec3,
+    pub pdf: f32,
+    pub lobe: Lobe,
+}
+
+impl ScatterRecord {
+    pub fn new(direction: Vec3, weight: Vec3, pdf: f32, lobe: Lobe) -> Self {
+        Self { direction, weight, pdf, lobe }
+    }
+
+    pub fn is_specular(&self) -> bool {
+        self.lobe == Lobe::Specular
+    }
+}
+
 pub trait Material {
-    fn scatter(&self, ray: &Ray, hit_record: &mut HitRecord) -> (bool, Ray, Vec3);
-}
\ No newline at end of file
+    // Picks the direction light continues in, None if the light is absorbed. The random numbers
+    // come from the sampler.
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord>;
+
+    fn emitted(&self, _hit_record: &HitRecord) -> Vec3 {
+        Vec3(0.0, 0.0, 0.0)
+    }
+
+    // Fraction of the light arriving from direction that is scattered along the incoming ray,
+    // including the cosine term. Zero for specular lobes.
+    fn eval(&self, _ray: &Ray, _hit_record: &HitRecord, _direction: Vec3) -> Vec3 {
+        Vec3(0.0, 0.0, 0.0)
+    }
+
+    // Density over solid angle with which sample picks direction. Zero for specular lobes.
+    fn pdf(&self, _ray: &Ray, _hit_record: &HitRecord, _direction: Vec3) -> f32 {
+        0.0
+    }
+
+    // Phase functions scatter inside of media, where there's no surface to take a cosine with
+    fn is_volume(&self) -> bool {
+        false
+    }
+}
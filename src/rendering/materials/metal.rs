@@ -1,5 +1,7 @@
-use crate::rendering::materials::material::Material;
+use std::f32::consts::PI;
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
 use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
 use crate::rendering::shapes::hittable::HitRecord;
 use crate::rendering::vector_math::Vec3;
 
@@ -21,10 +23,48 @@ impl Clone for Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, hit_record: &mut HitRecord) -> (bool, Ray, Vec3) {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
         let reflected = Vec3::reflect(&ray.direction().unit(), &hit_record.normal);
-        let scatter = Ray::new(hit_record.position, reflected + self.fuzz*Vec3::random_in_unit_sphere());
-        let color = self.color;
-        (scatter.direction() * hit_record.normal > 0.0, scatter, color)
+        if self.fuzz <= 0.0 {
+            return Some(ScatterRecord::new(reflected, self.color, 0.0, Lobe::Specular));
+        }
+
+        let direction = reflected + self.fuzz*Vec3::sample_in_unit_sphere(sampler.get_2d(), sampler.get_1d());
+        if direction * hit_record.normal <= 0.0 {
+            return None;
+        }
+
+        let pdf = self.pdf(ray, hit_record, direction);
+        Some(ScatterRecord::new(direction, self.color, pdf, Lobe::Glossy))
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        if direction * hit_record.normal <= 0.0 {
+            return Vec3(0.0, 0.0, 0.0);
+        }
+        self.color * self.pdf(ray, hit_record, direction)
+    }
+
+    // The fuzz offsets the mirror direction by a random point in a sphere, so the density of a
+    // direction is the part of that sphere's volume lying along it
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        if self.fuzz <= 0.0 {
+            return 0.0;
+        }
+
+        let reflected = Vec3::reflect(&ray.direction().unit(), &hit_record.normal);
+        let cosine = reflected * direction.unit();
+        let discriminant = cosine * cosine - 1.0 + self.fuzz * self.fuzz;
+        if discriminant <= 0.0 {
+            return 0.0;
+        }
+
+        let near = (cosine - discriminant.sqrt()).max(0.0);
+        let far = cosine + discriminant.sqrt();
+        if far <= 0.0 {
+            return 0.0;
+        }
+
+        (far.powi(3) - near.powi(3)) / (4.0 * PI * self.fuzz.powi(3))
     }
 }
\ No newline at end of file
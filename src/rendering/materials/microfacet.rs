@@ -0,0 +1,135 @@
+use std::f32::consts::PI;
+use crate::rendering::vector_math::Vec3;
+
+// GGX / Trowbridge-Reitz distribution of microfacet normals. All directions are in the local
+// frame of the surface, where the normal is +z.
+pub struct Ggx {
+    alpha: f32,
+}
+
+impl Ggx {
+    // Roughness is squared, so it changes the look more evenly between 0 and 1
+    pub fn new(roughness: f32) -> Self {
+        let roughness = roughness.clamp(0.0, 1.0);
+        Self { alpha: (roughness * roughness).max(1e-4) }
+    }
+
+    // Below this the lobe is too narrow to sample, so it is treated as a perfect mirror
+    pub fn is_smooth(&self) -> bool {
+        self.alpha < 1e-3
+    }
+
+    pub fn distribution(&self, m: Vec3) -> f32 {
+        if m.2 <= 0.0 {
+            return 0.0;
+        }
+        let t = (m.0 * m.0 + m.1 * m.1) / (self.alpha * self.alpha) + m.2 * m.2;
+        1.0 / (PI * self.alpha * self.alpha * t * t)
+    }
+
+    fn lambda(&self, w: Vec3) -> f32 {
+        let cos2 = w.2 * w.2;
+        if cos2 == 0.0 {
+            return f32::INFINITY;
+        }
+        let tan2 = (w.0 * w.0 + w.1 * w.1) / cos2;
+        ((1.0 + self.alpha * self.alpha * tan2).sqrt() - 1.0) / 2.0
+    }
+
+    // Smith masking, the fraction of microfacets facing w that aren't hidden behind others
+    pub fn masking(&self, w: Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    // Height correlated masking and shadowing for both directions
+    pub fn masking_shadowing(&self, wo: Vec3, wi: Vec3) -> f32 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    // Density of the normals visible from wo
+    pub fn visible_pdf(&self, wo: Vec3, m: Vec3) -> f32 {
+        if wo.2 <= 0.0 {
+            return 0.0;
+        }
+        self.masking(wo) * (wo * m).max(0.0) * self.distribution(m) / wo.2
+    }
+
+    // Samples only normals visible from wo (Heitz 2018), which wastes far fewer samples at grazing
+    // angles than sampling the distribution itself
+    pub fn sample_visible(&self, wo: Vec3, u1: f32, u2: f32) -> Vec3 {
+        let vh = Vec3(self.alpha * wo.0, self.alpha * wo.1, wo.2).unit();
+
+        let length_squared = vh.0 * vh.0 + vh.1 * vh.1;
+        let t1 = if length_squared > 0.0 {
+            Vec3(-vh.1, vh.0, 0.0) / length_squared.sqrt()
+        } else {
+            Vec3(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(&t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.2);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * r * phi.sin();
+
+        let nh = t1 * p1 + t2 * p2 + vh * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+        Vec3(self.alpha * nh.0, self.alpha * nh.1, nh.2.max(1e-6)).unit()
+    }
+}
+
+// Fraction of light reflected at the boundary to a medium with relative index of refraction eta.
+// Total internal reflection gives 1.
+pub fn fresnel_dielectric(cos_theta: f32, eta: f32) -> f32 {
+    let cos_i = cos_theta.clamp(-1.0, 1.0).abs();
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    (parallel * parallel + perpendicular * perpendicular) / 2.0
+}
+
+fn fresnel_complex(cos_i: f32, eta: f32, k: f32) -> f32 {
+    let cos2 = cos_i * cos_i;
+    let sin2 = 1.0 - cos2;
+    let t0 = eta * eta - k * k - sin2;
+    let a2b2 = (t0 * t0 + 4.0 * eta * eta * k * k).sqrt();
+    let a = (0.5 * (a2b2 + t0)).max(0.0).sqrt();
+
+    let t1 = a2b2 + cos2;
+    let t2 = 2.0 * a * cos_i;
+    let perpendicular = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let parallel = perpendicular * (t3 - t4) / (t3 + t4);
+
+    (parallel + perpendicular) / 2.0
+}
+
+// Reflectance of a metal with the complex index of refraction eta + ik, per color channel
+pub fn fresnel_conductor(cos_theta: f32, eta: Vec3, k: Vec3) -> Vec3 {
+    let cos_i = cos_theta.clamp(0.0, 1.0);
+    Vec3(
+        fresnel_complex(cos_i, eta.0, k.0),
+        fresnel_complex(cos_i, eta.1, k.1),
+        fresnel_complex(cos_i, eta.2, k.2),
+    )
+}
+
+// Direction of wo after passing the boundary to a medium with relative index of refraction eta,
+// None on total internal reflection. The normal has to be on the side of wo.
+pub fn refract(wo: Vec3, normal: Vec3, eta: f32) -> Option<Vec3> {
+    let cos_i = wo * normal;
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-1.0 * wo / eta + normal * (cos_i / eta - cos_t))
+}
@@ -1,3 +1,12 @@
 pub mod material;
 pub mod lambertian;
-pub mod metal;
\ No newline at end of file
+pub mod metal;
+pub mod isotropic;
+pub mod henyey_greenstein;
+pub mod diffuse_light;
+pub mod microfacet;
+pub mod conductor;
+pub mod dielectric;
+pub mod principled;
+pub mod thin_film;
+pub mod coated;
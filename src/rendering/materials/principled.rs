@@ -0,0 +1,256 @@
+use std::f32::consts::PI;
+use crate::rendering::materials::dielectric::Dielectric;
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::materials::microfacet::Ggx;
+use crate::rendering::onb::Onb;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::textures::texture::{SolidColor, Texture};
+use crate::rendering::vector_math::Vec3;
+
+// Perfectly smooth lobes can't be mixed with the others, so the roughness is kept above this
+const MIN_ROUGHNESS: f32 = 0.05;
+
+fn schlick(cosine: f32, f0: Vec3) -> Vec3 {
+    let factor = (1.0 - cosine.clamp(0.0, 1.0)).powi(5);
+    f0 + (Vec3(1.0, 1.0, 1.0) - f0) * factor
+}
+
+fn luminance(color: Vec3) -> f32 {
+    0.2126 * color.0 + 0.7152 * color.1 + 0.0722 * color.2
+}
+
+fn lerp(a: Vec3, b: Vec3, t: f32) -> Vec3 {
+    a * (1.0 - t) + b * t
+}
+
+// GGX reflection with a Schlick fresnel term, including the cosine. Returns the value and pdf.
+fn microfacet_reflection(distribution: &Ggx, f0: Vec3, wo: Vec3, wi: Vec3) -> (Vec3, f32) {
+    if wo.2 <= 0.0 || wi.2 <= 0.0 {
+        return (Vec3(0.0, 0.0, 0.0), 0.0);
+    }
+
+    let m = (wo + wi).unit();
+    let d = distribution.distribution(m);
+    let g = distribution.masking_shadowing(wo, wi);
+    let value = schlick(wo * m, f0) * (d * g / (4.0 * wo.2));
+    let pdf = distribution.visible_pdf(wo, m) / (4.0 * (wo * m));
+
+    (value, pdf)
+}
+
+// Parameters of the material at a single point, after looking up all textures
+struct Parameters {
+    base_color: Vec3,
+    metallic: f32,
+    roughness: f32,
+    specular: f32,
+    sheen: Vec3,
+    clearcoat: f32,
+    clearcoat_roughness: f32,
+    transmission: f32,
+}
+
+impl Parameters {
+    // Reflectance at normal incidence. Dielectrics reflect 8% at most, metals their base color.
+    fn f0(&self) -> Vec3 {
+        let dielectric = 0.08 * self.specular;
+        lerp(Vec3(dielectric, dielectric, dielectric), self.base_color, self.metallic)
+    }
+
+    // Probabilities of sampling the diffuse, specular, transmission and clearcoat lobes, roughly
+    // by how much each of them reflects
+    fn lobe_probabilities(&self, cos_o: f32) -> [f32; 4] {
+        let coat = self.clearcoat * schlick(cos_o, Vec3(0.04, 0.04, 0.04)).0;
+        let transmission = (1.0 - coat) * (1.0 - self.metallic) * self.transmission;
+        let opaque = (1.0 - coat) - transmission;
+
+        let specular = luminance(schlick(cos_o, self.f0()));
+        let diffuse = (1.0 - self.metallic) * (luminance(self.base_color) + luminance(self.sheen));
+        let share = if specular + diffuse > 0.0 { diffuse / (specular + diffuse) } else { 0.0 };
+
+        [opaque * share, opaque * (1.0 - share), transmission, coat]
+    }
+}
+
+// Single material covering everything from plastic over metal to glass, following the glTF
+// metallic-roughness model. Every parameter can be textured.
+pub struct Principled {
+    base_color: Box<dyn Texture>,
+    metallic: Box<dyn Texture>,
+    roughness: Box<dyn Texture>,
+    specular: Box<dyn Texture>,
+    sheen: Box<dyn Texture>,
+    clearcoat: Box<dyn Texture>,
+    clearcoat_roughness: Box<dyn Texture>,
+    transmission: Box<dyn Texture>,
+    emission: Box<dyn Texture>,
+    ior: f32,
+}
+
+impl Principled {
+    pub fn new(base_color: Vec3, metallic: f32, roughness: f32) -> Self {
+        Self {
+            base_color: Box::new(SolidColor::new(base_color)),
+            metallic: Box::new(SolidColor::scalar(metallic)),
+            roughness: Box::new(SolidColor::scalar(roughness)),
+            specular: Box::new(SolidColor::scalar(0.5)),
+            sheen: Box::new(SolidColor::scalar(0.0)),
+            clearcoat: Box::new(SolidColor::scalar(0.0)),
+            clearcoat_roughness: Box::new(SolidColor::scalar(0.03)),
+            transmission: Box::new(SolidColor::scalar(0.0)),
+            emission: Box::new(SolidColor::scalar(0.0)),
+            ior: 1.5,
+        }
+    }
+
+    pub fn set_base_color(&mut self, base_color: Box<dyn Texture>) {
+        self.base_color = base_color;
+    }
+
+    pub fn set_metallic(&mut self, metallic: Box<dyn Texture>) {
+        self.metallic = metallic;
+    }
+
+    pub fn set_roughness(&mut self, roughness: Box<dyn Texture>) {
+        self.roughness = roughness;
+    }
+
+    // 0.5 is the usual 4% reflectance of dielectrics
+    pub fn set_specular(&mut self, specular: Box<dyn Texture>) {
+        self.specular = specular;
+    }
+
+    // Color of the soft highlight at grazing angles seen on cloth
+    pub fn set_sheen(&mut self, sheen: Box<dyn Texture>) {
+        self.sheen = sheen;
+    }
+
+    pub fn set_clearcoat(&mut self, clearcoat: Box<dyn Texture>, roughness: Box<dyn Texture>) {
+        self.clearcoat = clearcoat;
+        self.clearcoat_roughness = roughness;
+    }
+
+    // Fraction of the light passing through the surface, tinted by the base color
+    pub fn set_transmission(&mut self, transmission: Box<dyn Texture>, ior: f32) {
+        self.transmission = transmission;
+        self.ior = ior;
+    }
+
+    pub fn set_emission(&mut self, emission: Box<dyn Texture>) {
+        self.emission = emission;
+    }
+
+    fn parameters(&self, hit_record: &HitRecord) -> Parameters {
+        let value = |texture: &dyn Texture| texture.value(hit_record.u, hit_record.v, hit_record.position);
+        let scalar = |texture: &dyn Texture| value(texture).0.clamp(0.0, 1.0);
+
+        Parameters {
+            base_color: value(self.base_color.as_ref()),
+            metallic: scalar(self.metallic.as_ref()),
+            roughness: scalar(self.roughness.as_ref()).max(MIN_ROUGHNESS),
+            specular: scalar(self.specular.as_ref()),
+            sheen: value(self.sheen.as_ref()),
+            clearcoat: scalar(self.clearcoat.as_ref()),
+            clearcoat_roughness: scalar(self.clearcoat_roughness.as_ref()).max(MIN_ROUGHNESS),
+            transmission: scalar(self.transmission.as_ref()),
+        }
+    }
+
+    // Value and pdf of all lobes together for the world space direction
+    fn evaluate(&self, parameters: &Parameters, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> (Vec3, f32) {
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        let wi = onb.to_local(direction.unit());
+        if wo.2 <= 0.0 {
+            return (Vec3(0.0, 0.0, 0.0), 0.0);
+        }
+
+        let probabilities = parameters.lobe_probabilities(wo.2);
+        let coat = parameters.clearcoat * schlick(wo.2, Vec3(0.04, 0.04, 0.04)).0;
+        let transmission = (1.0 - parameters.metallic) * parameters.transmission;
+
+        let mut value = Vec3(0.0, 0.0, 0.0);
+        let mut pdf = 0.0;
+
+        if wi.2 > 0.0 {
+            // Light not reflected by the dielectric specular layer reaches the diffuse base
+            let f0 = parameters.f0();
+            let dielectric = 0.08 * parameters.specular;
+            let diffuse_weight = (1.0 - parameters.metallic) * (1.0 - schlick(wo.2, Vec3(dielectric, dielectric, dielectric)).0);
+            let half = (wo + wi).unit();
+            let diffuse = parameters.base_color * (diffuse_weight * wi.2 / PI)
+                + parameters.sheen * ((1.0 - parameters.metallic) * (1.0 - wi * half).powi(5) * wi.2);
+
+            let (specular, specular_pdf) = microfacet_reflection(&Ggx::new(parameters.roughness), f0, wo, wi);
+            let (clearcoat, clearcoat_pdf) = microfacet_reflection(&Ggx::new(parameters.clearcoat_roughness), Vec3(0.04, 0.04, 0.04), wo, wi);
+
+            value = (diffuse + specular) * ((1.0 - coat) * (1.0 - transmission)) + clearcoat * parameters.clearcoat;
+            pdf = probabilities[0] * wi.2 / PI + probabilities[1] * specular_pdf + probabilities[3] * clearcoat_pdf;
+        }
+
+        if transmission > 0.0 {
+            let glass = Dielectric::new(self.ior, parameters.roughness);
+            value = value + glass.eval(ray, hit_record, direction).mul(&parameters.base_color) * ((1.0 - coat) * transmission);
+            pdf += probabilities[2] * glass.pdf(ray, hit_record, direction);
+        }
+
+        (value, pdf)
+    }
+}
+
+impl Material for Principled {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let parameters = self.parameters(hit_record);
+        let onb = Onb::new(hit_record.normal);
+        let wo = onb.to_local(-1.0 * ray.direction().unit());
+        if wo.2 <= 0.0 {
+            return None;
+        }
+
+        let probabilities = parameters.lobe_probabilities(wo.2);
+        let mut choice = sampler.get_1d() * probabilities.iter().sum::<f32>();
+        let (u1, u2) = sampler.get_2d();
+        let mut lobe = 0;
+        while lobe < 3 && choice >= probabilities[lobe] {
+            choice -= probabilities[lobe];
+            lobe += 1;
+        }
+
+        let direction = match lobe {
+            0 => onb.local(Vec3::sample_cosine_direction((u1, u2))),
+            1 | 3 => {
+                let roughness = if lobe == 1 { parameters.roughness } else { parameters.clearcoat_roughness };
+                let m = Ggx::new(roughness).sample_visible(wo, u1, u2);
+                onb.local(-1.0 * wo + m * (2.0 * (wo * m)))
+            }
+            _ => Dielectric::new(self.ior, parameters.roughness).sample(ray, hit_record, sampler)?.direction,
+        };
+
+        // The weight covers all lobes, so it doesn't matter which one picked the direction
+        let (value, pdf) = self.evaluate(&parameters, ray, hit_record, direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let lobe = if lobe == 0 { Lobe::Diffuse } else { Lobe::Glossy };
+        Some(ScatterRecord::new(direction, value / pdf, pdf, lobe))
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Vec3 {
+        if hit_record.font_face {
+            self.emission.value(hit_record.u, hit_record.v, hit_record.position)
+        } else {
+            Vec3(0.0, 0.0, 0.0)
+        }
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        self.evaluate(&self.parameters(hit_record), ray, hit_record, direction).0
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        self.evaluate(&self.parameters(hit_record), ray, hit_record, direction).1
+    }
+}
@@ -0,0 +1,116 @@
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::hittable::HitRecord;
+use crate::rendering::vector_math::Vec3;
+
+const WAVELENGTHS: usize = 16;
+const MIN_WAVELENGTH: f32 = 380.0;
+const MAX_WAVELENGTH: f32 = 780.0;
+
+fn gaussian(wavelength: f32, mean: f32, below: f32, above: f32) -> f32 {
+    let sigma = if wavelength < mean { below } else { above };
+    let t = (wavelength - mean) / sigma;
+    (-0.5 * t * t).exp()
+}
+
+// Analytic fit of the CIE 1931 color matching functions (Wyman et al. 2013)
+fn color_matching(wavelength: f32) -> Vec3 {
+    Vec3(
+        1.056 * gaussian(wavelength, 599.8, 37.9, 31.0) + 0.362 * gaussian(wavelength, 442.0, 16.0, 26.7)
+            - 0.065 * gaussian(wavelength, 501.1, 20.4, 26.2),
+        0.821 * gaussian(wavelength, 568.8, 46.9, 40.5) + 0.286 * gaussian(wavelength, 530.9, 16.3, 31.1),
+        1.217 * gaussian(wavelength, 437.0, 11.8, 36.0) + 0.681 * gaussian(wavelength, 459.0, 26.0, 13.8),
+    )
+}
+
+fn xyz_to_rgb(xyz: Vec3) -> Vec3 {
+    Vec3(
+        3.2406 * xyz.0 - 1.5372 * xyz.1 - 0.4986 * xyz.2,
+        -0.9689 * xyz.0 + 1.8758 * xyz.1 + 0.0415 * xyz.2,
+        0.0557 * xyz.0 - 0.2040 * xyz.1 + 1.0570 * xyz.2,
+    )
+}
+
+fn wavelength(index: usize) -> f32 {
+    MIN_WAVELENGTH + (MAX_WAVELENGTH - MIN_WAVELENGTH) * (index as f32 + 0.5) / WAVELENGTHS as f32
+}
+
+// Amplitude reflection coefficients for s and p polarized light, going from index n1 to n2
+fn amplitudes(cos_1: f32, cos_2: f32, n1: f32, n2: f32) -> (f32, f32) {
+    let s = (n1 * cos_1 - n2 * cos_2) / (n1 * cos_1 + n2 * cos_2);
+    let p = (n2 * cos_1 - n1 * cos_2) / (n2 * cos_1 + n1 * cos_2);
+    (s, p)
+}
+
+// Light reflected by the top and the bottom of the film interferes depending on its wavelength,
+// which gives the colors of soap bubbles and oil slicks. The reflectance is computed for a range of
+// wavelengths and converted to RGB, where a reflectance equal for all wavelengths stays gray.
+pub fn thin_film_reflectance(cos_theta: f32, thickness: f32, film_ior: f32, outside_ior: f32, substrate_ior: f32) -> Vec3 {
+    let cos_1 = cos_theta.clamp(0.0, 1.0);
+    let sin2_1 = 1.0 - cos_1 * cos_1;
+
+    // Total internal reflection inside the film or at the substrate reflects everything
+    let sin2_2 = sin2_1 * (outside_ior / film_ior).powi(2);
+    let sin2_3 = sin2_1 * (outside_ior / substrate_ior).powi(2);
+    if sin2_2 >= 1.0 || sin2_3 >= 1.0 {
+        return Vec3(1.0, 1.0, 1.0);
+    }
+    let cos_2 = (1.0 - sin2_2).sqrt();
+    let cos_3 = (1.0 - sin2_3).sqrt();
+
+    let (top_s, top_p) = amplitudes(cos_1, cos_2, outside_ior, film_ior);
+    let (bottom_s, bottom_p) = amplitudes(cos_2, cos_3, film_ior, substrate_ior);
+
+    let airy = |top: f32, bottom: f32, phase: f32| {
+        let cross = 2.0 * top * bottom * phase.cos();
+        (top * top + bottom * bottom + cross) / (1.0 + top * top * bottom * bottom + cross)
+    };
+
+    let mut xyz = Vec3(0.0, 0.0, 0.0);
+    let mut white = Vec3(0.0, 0.0, 0.0);
+    for index in 0..WAVELENGTHS {
+        let wavelength = wavelength(index);
+        let phase = 4.0 * std::f32::consts::PI * film_ior * thickness * cos_2 / wavelength;
+        let reflectance = (airy(top_s, bottom_s, phase) + airy(top_p, bottom_p, phase)) / 2.0;
+
+        let matching = color_matching(wavelength);
+        xyz = xyz + matching * reflectance;
+        white = white + matching;
+    }
+
+    let rgb = xyz_to_rgb(xyz);
+    let white = xyz_to_rgb(white);
+    Vec3(rgb.0 / white.0, rgb.1 / white.1, rgb.2 / white.2).max(&Vec3(0.0, 0.0, 0.0)).min(&Vec3(1.0, 1.0, 1.0))
+}
+
+// Soap bubble like film with air on both sides. It's so thin that light passes through without
+// being bent, only the reflection is colored by interference.
+pub struct ThinFilm {
+    thickness: f32,
+    ior: f32,
+}
+
+impl ThinFilm {
+    // Thickness in nanometers, soap bubbles are a few hundred nanometers thick
+    pub fn new(thickness: f32, ior: f32) -> Self {
+        Self { thickness, ior }
+    }
+}
+
+impl Material for ThinFilm {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let direction = ray.direction().unit();
+        let cosine = -(direction * hit_record.normal);
+        let reflectance = thin_film_reflectance(cosine, self.thickness, self.ior, 1.0, 1.0);
+
+        let probability = ((reflectance.0 + reflectance.1 + reflectance.2) / 3.0).clamp(0.01, 0.99);
+        if sampler.get_1d() < probability {
+            let reflected = Vec3::reflect(&direction, &hit_record.normal);
+            Some(ScatterRecord::new(reflected, reflectance / probability, 0.0, Lobe::Specular))
+        } else {
+            let transmittance = Vec3(1.0, 1.0, 1.0) - reflectance;
+            Some(ScatterRecord::new(direction, transmittance / (1.0 - probability), 0.0, Lobe::Specular))
+        }
+    }
+}
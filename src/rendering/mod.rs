@@ -4,4 +4,17 @@ pub mod ray;
 pub mod vector_math;
 pub mod shapes;
 pub mod camera;
-pub mod materials;
\ No newline at end of file
+pub mod materials;
+pub mod onb;
+pub mod polynomial;
+pub mod sdf;
+pub mod volumes;
+pub mod transform;
+pub mod scene;
+pub mod integrators;
+pub mod lights;
+pub mod textures;
+pub mod film;
+pub mod distribution;
+pub mod samplers;
+pub mod random;
@@ -0,0 +1,27 @@
+use crate::rendering::vector_math::Vec3;
+
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    pub fn new(w: Vec3) -> Self {
+        let w = w.unit();
+        let helper = if w.0.abs() > 0.9 { Vec3(0.0, 1.0, 0.0) } else { Vec3(1.0, 0.0, 0.0) };
+        let v = w.cross(&helper).unit();
+        let u = w.cross(&v);
+
+        Self { u, v, w }
+    }
+
+    pub fn local(&self, a: Vec3) -> Vec3 {
+        self.u * a.0 + self.v * a.1 + self.w * a.2
+    }
+
+    pub fn to_local(&self, a: Vec3) -> Vec3 {
+        Vec3(a * self.u, a * self.v, a * self.w)
+    }
+}
+
@@ -0,0 +1,142 @@
+use std::f64::consts::PI;
+
+const EPSILON: f64 = 1e-9;
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < EPSILON
+}
+
+// Real roots of a*x^2 + b*x + c
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if is_zero(a) {
+        if is_zero(b) {
+            return vec![];
+        }
+        return vec![-c / b];
+    }
+
+    let p = b / (2.0 * a);
+    let q = c / a;
+    let discriminant = p * p - q;
+
+    if is_zero(discriminant) {
+        vec![-p]
+    } else if discriminant < 0.0 {
+        vec![]
+    } else {
+        let sqrt = discriminant.sqrt();
+        vec![sqrt - p, -sqrt - p]
+    }
+}
+
+// Real roots of a*x^3 + b*x^2 + c*x + d
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return solve_quadratic(b, c, d);
+    }
+
+    let a_ = b / a;
+    let b_ = c / a;
+    let c_ = d / a;
+
+    // Substitute x = y - a_/3 to eliminate the quadric term: y^3 + p*y + q = 0
+    let sq_a = a_ * a_;
+    let p = 1.0 / 3.0 * (-1.0 / 3.0 * sq_a + b_);
+    let q = 1.0 / 2.0 * (2.0 / 27.0 * a_ * sq_a - 1.0 / 3.0 * a_ * b_ + c_);
+
+    let cb_p = p * p * p;
+    let discriminant = q * q + cb_p;
+
+    let mut roots = if is_zero(discriminant) {
+        if is_zero(q) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if discriminant < 0.0 {
+        let phi = 1.0 / 3.0 * (-q / (-cb_p).sqrt()).clamp(-1.0, 1.0).acos();
+        let t = 2.0 * (-p).sqrt();
+        vec![t * phi.cos(), -t * (phi + PI / 3.0).cos(), -t * (phi - PI / 3.0).cos()]
+    } else {
+        let sqrt = discriminant.sqrt();
+        vec![(sqrt - q).cbrt() - (sqrt + q).cbrt()]
+    };
+
+    let sub = 1.0 / 3.0 * a_;
+    for root in roots.iter_mut() {
+        *root -= sub;
+    }
+
+    roots
+}
+
+// Real roots of a*x^4 + b*x^3 + c*x^2 + d*x + e
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return solve_cubic(b, c, d, e);
+    }
+
+    let a_ = b / a;
+    let b_ = c / a;
+    let c_ = d / a;
+    let d_ = e / a;
+
+    // Substitute x = y - a_/4 to eliminate the cubic term: y^4 + p*y^2 + q*y + r = 0
+    let sq_a = a_ * a_;
+    let p = -3.0 / 8.0 * sq_a + b_;
+    let q = 1.0 / 8.0 * sq_a * a_ - 1.0 / 2.0 * a_ * b_ + c_;
+    let r = -3.0 / 256.0 * sq_a * sq_a + 1.0 / 16.0 * sq_a * b_ - 1.0 / 4.0 * a_ * c_ + d_;
+
+    let mut roots = Vec::new();
+
+    if is_zero(r) {
+        // No absolute term: y * (y^3 + p*y + q) = 0
+        roots.push(0.0);
+        roots.append(&mut solve_cubic(1.0, 0.0, p, q));
+    } else {
+        // Solve the resolvent cubic and use one real root to split into two quadrics
+        let resolvent = solve_cubic(1.0, -1.0 / 2.0 * p, -r, 1.0 / 2.0 * r * p - 1.0 / 8.0 * q * q);
+        let z = resolvent[0];
+
+        let mut u = z * z - r;
+        let mut v = 2.0 * z - p;
+
+        if is_zero(u) {
+            u = 0.0;
+        } else if u > 0.0 {
+            u = u.sqrt();
+        } else {
+            return vec![];
+        }
+
+        if is_zero(v) {
+            v = 0.0;
+        } else if v > 0.0 {
+            v = v.sqrt();
+        } else {
+            return vec![];
+        }
+
+        let sign = if q < 0.0 { -1.0 } else { 1.0 };
+        roots.append(&mut solve_quadratic(1.0, sign * v, z - u));
+        roots.append(&mut solve_quadratic(1.0, -sign * v, z + u));
+    }
+
+    let sub = 1.0 / 4.0 * a_;
+    for root in roots.iter_mut() {
+        *root -= sub;
+
+        // Polish the analytic root, the closed form loses a lot of precision
+        for _ in 0..2 {
+            let value = (((a * *root + b) * *root + c) * *root + d) * *root + e;
+            let derivative = ((4.0 * a * *root + 3.0 * b) * *root + 2.0 * c) * *root + d;
+            if is_zero(derivative) {
+                break;
+            }
+            *root -= value / derivative;
+        }
+    }
+
+    roots
+}
@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+use rand::rngs::SmallRng;
+use rand::{RngCore, SeedableRng};
+use crate::rendering::samplers::sampler::hash;
+
+// Random numbers of the thread, restarted for every sample of a pixel from the seed of the render
+// and the sample, so every sample gets the same numbers no matter which thread renders it or when
+struct Stream {
+    seed: u64,
+    rng: SmallRng,
+}
+
+thread_local! {
+    static STREAM: RefCell<Stream> = RefCell::new(Stream { seed: 0, rng: SmallRng::seed_from_u64(0) });
+}
+
+fn derive(seed: u64, key: &[u32]) -> SmallRng {
+    let key = hash(key);
+    let low = hash(&[seed as u32, (seed >> 32) as u32, key]);
+    let high = hash(&[low, key]);
+    SmallRng::seed_from_u64(((high as u64) << 32) | low as u64)
+}
+
+// Starts the numbers of one sample of a pixel
+pub fn start_sample(seed: u64, pixel: (u32, u32), index: u32) {
+    STREAM.with(|stream| {
+        let mut stream = stream.borrow_mut();
+        stream.seed = seed;
+        stream.rng = derive(seed, &[pixel.0, pixel.1, index]);
+    });
+}
+
+// Runs the closure on numbers picked by the key instead of those of the current sample, for work
+// that's shared between samples, and continues the sample afterwards where it left off
+pub fn with_stream<T>(key: &[u32], run: impl FnOnce() -> T) -> T {
+    let previous = STREAM.with(|stream| {
+        let mut stream = stream.borrow_mut();
+        let rng = derive(stream.seed, key);
+        std::mem::replace(&mut stream.rng, rng)
+    });
+
+    let result = run();
+
+    STREAM.with(|stream| stream.borrow_mut().rng = previous);
+    result
+}
+
+// Handle to the numbers of the thread, used like rand::thread_rng
+pub struct ThreadStream;
+
+pub fn rng() -> ThreadStream {
+    ThreadStream
+}
+
+impl RngCore for ThreadStream {
+    fn next_u32(&mut self) -> u32 {
+        STREAM.with(|stream| stream.borrow_mut().rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        STREAM.with(|stream| stream.borrow_mut().rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, destination: &mut [u8]) {
+        STREAM.with(|stream| stream.borrow_mut().rng.fill_bytes(destination))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(seed: u64, pixel: (u32, u32), index: u32) -> Vec<u32> {
+        start_sample(seed, pixel, index);
+        (0..8).map(|_| rng().next_u32()).collect()
+    }
+
+    #[test]
+    fn samples_do_not_depend_on_the_order_they_are_started_in() {
+        let first = numbers(3, (1, 2), 0);
+        let second = numbers(3, (4, 5), 6);
+
+        // Another thread starting them the other way round, with a shared stream in between
+        let handle = std::thread::spawn(|| {
+            let second = numbers(3, (4, 5), 6);
+            with_stream(&[9], || rng().next_u32());
+            (numbers(3, (1, 2), 0), second)
+        });
+        assert_eq!(handle.join().unwrap(), (first.clone(), second.clone()));
+
+        assert_ne!(first, second);
+        assert_ne!(first, numbers(4, (1, 2), 0));
+    }
+}
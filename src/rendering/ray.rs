@@ -3,11 +3,12 @@ use crate::rendering::vector_math::Vec3;
 pub struct Ray {
     origin: Vec3,
     direction: Vec3,
+    time: f32,
 }
 
 impl Ray {
-    pub fn new(origin: Vec3, direction: Vec3) -> Self {
-        Self { origin, direction }
+    pub fn new(origin: Vec3, direction: Vec3, time: f32) -> Self {
+        Self { origin, direction, time }
     }
 
     pub fn at(&self, t: f32) -> Vec3 {
@@ -20,4 +21,7 @@ impl Ray {
     pub fn direction(&self) -> Vec3 {
         self.direction
     }
+    pub fn time(&self) -> f32 {
+        self.time
+    }
 }
\ No newline at end of file
@@ -1,23 +1,35 @@
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::{JoinHandle};
-use rand::Rng;
-use sdl2::pixels::Color;
-use sdl2::rect::Point;
 use crate::rendering::camera::Camera;
+use crate::rendering::film::Film;
 use crate::rendering::materials::lambertian::Lambertian;
 use crate::rendering::materials::metal::Metal;
 use crate::rendering::shapes::hittable::Hittable;
-use crate::rendering::raytracing::{calculate_color, float_to_u8_color, gamma_correction, Pixel};
-use crate::rendering::shapes::hittable_list::HittableList;
+use crate::rendering::integrators::integrator::{CameraSample, IntegratorKind};
+use crate::rendering::integrators::photon_mapping::PhotonPasses;
+use crate::rendering::lights::environment_light::{EnvironmentLight, EnvironmentMap};
+use crate::rendering::lights::sky_light::{Sky, SkyLight};
+use crate::rendering::random;
+use crate::rendering::raytracing::Pixel;
+use crate::rendering::samplers::sampler::SamplerKind;
+use crate::rendering::scene::Scene;
+use crate::rendering::shapes::bvh::Bvh;
 use crate::rendering::shapes::sphere::Sphere;
 use crate::rendering::vector_math::Vec3;
+use crate::rendering::volumes::fog::Fog;
 
 pub struct RendererProperties {
     size: (u32,u32),
     samples: u32,
     max_bounces: u32,
     thread_percentage: f32,
+    fog: Option<Fog>,
+    shutter: (f32, f32),
+    integrator: IntegratorKind,
+    sky: Option<Sky>,
+    sampler: SamplerKind,
+    seed: u64,
 }
 
 impl Clone for RendererProperties {
@@ -26,7 +38,13 @@ impl Clone for RendererProperties {
             size: self.size,
             samples: self.samples,
             max_bounces: self.max_bounces,
-            thread_percentage: self.thread_percentage
+            thread_percentage: self.thread_percentage,
+            fog: self.fog,
+            shutter: self.shutter,
+            integrator: self.integrator,
+            sky: self.sky,
+            sampler: self.sampler,
+            seed: self.seed,
         }
     }
 }
@@ -37,17 +55,18 @@ impl Copy for RendererProperties {
 
 impl RendererProperties {
     pub fn new(size: (u32, u32), samples: u32, max_bounces: u32, thread_percentage: f32) -> Self {
-        Self { size, samples, max_bounces, thread_percentage }
+        Self { size, samples, max_bounces, thread_percentage, fog: None, shutter: (0.0, 1.0), integrator: IntegratorKind::PathTracer, sky: None, sampler: SamplerKind::Sobol, seed: 0 }
     }
 }
 
 pub struct Renderer {
     properties: RendererProperties,
+    environment: Option<Arc<EnvironmentMap>>,
 }
 
 impl Renderer {
     pub fn new(properties: RendererProperties) -> Self {
-        Self { properties }
+        Self { properties, environment: None }
     }
 
     pub fn start_render(&mut self) -> (Arc<Mutex<Vec<Pixel>>>, Vec<JoinHandle<()>>, Arc<Mutex<bool>>) {
@@ -55,21 +74,39 @@ impl Renderer {
 
         let thread_amount = (self.properties.thread_percentage * num_cpus::get() as f32)as u32;
 
-        let lines_remaining = Arc::new(Mutex::new(self.properties.size.1));
+        // Pass over the image that's being rendered and the lines of it nobody took yet
+        let lines_remaining = Arc::new(Mutex::new((0, self.properties.size.1)));
         let queue_mutex = Arc::new(Mutex::new(Vec::new()));
         let stop_mutex = Arc::new(Mutex::new(false));
 
         let size = self.properties.size;
+        let film = Arc::new(Mutex::new(Film::new(size)));
+        let lines_finished = Arc::new(Mutex::new(0));
 
-        let camera = Camera::new(size, 2.0, 1.0);
+        let mut camera = Camera::new(size, 2.0, 1.0);
+        camera.set_shutter(self.properties.shutter.0, self.properties.shutter.1);
 
         let samples = self.properties.samples;
         let max_depth = self.properties.max_bounces;
+        let fog = self.properties.fog;
+        let integrator_kind = self.properties.integrator;
+        let sky = self.properties.sky;
+        let sampler_kind = self.properties.sampler;
+        let seed = self.properties.seed;
+        let photon_passes = Arc::new(PhotonPasses::new(samples));
+
+        // Passes over the image and samples per pixel in each. The next pass starts once every
+        // line of the last one is done, so the sum of every pixel is added up in the same order.
+        let (passes, pass_samples) = if integrator_kind.renders_in_passes() { (samples, 1) } else { (1, samples) };
 
         for _ in 0..thread_amount {
             let lines_remaining = Arc::clone(&lines_remaining);
             let queue_mutex = Arc::clone(&queue_mutex);
             let stop_mutex = Arc::clone(&stop_mutex);
+            let film = Arc::clone(&film);
+            let lines_finished = Arc::clone(&lines_finished);
+            let environment = self.environment.clone();
+            let photon_passes = Arc::clone(&photon_passes);
 
             let mat_ground = Box::new(Lambertian::new(Vec3(0.8,0.8,0.0)));
             let mat_center = Box::new(Lambertian::new(Vec3(0.7,0.3,0.3)));
@@ -79,18 +116,26 @@ impl Renderer {
 
             handles.push(thread::spawn(move || {
                 //TEMPORARY
-                let mut world = HittableList::new();
-                    world.add(Box::new(Sphere::new(Vec3(0.0,1.0,-2.0), 0.3, mat_new)));
-                    world.add(Box::new(Sphere::new(Vec3(0.0,-100.5,-2.0), 100.0, mat_ground)));
-                    world.add(Box::new(Sphere::new(Vec3(0.0,0.0,-2.0), 0.5,mat_center)));
-                    world.add(Box::new(Sphere::new(Vec3(-1.0,0.0,-2.0), 0.5,mat_left)));
-                    world.add(Box::new(Sphere::new(Vec3(1.0,0.0,-2.0), 0.5,mat_right)));
-
-                let world: Box<dyn Hittable> = Box::new(world);
-
-                let mut rng = rand::thread_rng();
+                let objects: Vec<Box<dyn Hittable>> = vec![
+                    Box::new(Sphere::new(Vec3(0.0,1.0,-2.0), 0.3, mat_new)),
+                    Box::new(Sphere::new(Vec3(0.0,-100.5,-2.0), 100.0, mat_ground)),
+                    Box::new(Sphere::new(Vec3(0.0,0.0,-2.0), 0.5,mat_center)),
+                    Box::new(Sphere::new(Vec3(-1.0,0.0,-2.0), 0.5,mat_left)),
+                    Box::new(Sphere::new(Vec3(1.0,0.0,-2.0), 0.5,mat_right)),
+                ];
+
+                let mut scene = Scene::new(Box::new(Bvh::new(objects)));
+                // An environment map takes precedence over the physical sky
+                if let Some(environment) = environment {
+                    scene.set_environment(Box::new(EnvironmentLight::new(environment)));
+                } else if let Some(sky) = sky {
+                    scene.set_environment(Box::new(SkyLight::new(sky)));
+                }
+                let integrator = integrator_kind.create(max_depth, fog, &photon_passes);
+                let mut sampler = sampler_kind.create(samples, seed);
 
                 let mut temp = Vec::new();
+                let mut splats = Vec::new();
                 let parts = 10;
                 let pixels_per_update = (size.0 as f32 / parts as f32).ceil() as i32;
 
@@ -101,15 +146,15 @@ impl Renderer {
                     }
 
                     // If available, accept next job!
-                    let y: u32;
+                    let (pass, y): (u32, u32);
                     {
                         let mut remaining = lines_remaining.lock().unwrap();
-                        if *remaining <= 0 {
+                        if remaining.1 <= 0 {
                             continue;
                         }
-                        *remaining -= 1;
+                        remaining.1 -= 1;
 
-                        y = *remaining;
+                        (pass, y) = *remaining;
                     }
 
                     // Rendering Process
@@ -120,29 +165,61 @@ impl Renderer {
 
                             let mut color = Vec3(0.0, 0.0, 0.0);
 
-                            for _ in 0..samples {
-                                let u = (y as f32 + rng.gen::<f32>()) / (size.1 - 1) as f32;
-                                let v = (x as f32 + rng.gen::<f32>()) / (size.0 - 1) as f32;
-
-                                let ray = camera.get_ray(u,v);
-                                color = color + calculate_color(&ray, &world, max_depth);
+                            // Every sample starts its own random numbers, so the image only
+                            // depends on the seed and not on the threads
+                            for index in pass * pass_samples..(pass + 1) * pass_samples {
+                                random::start_sample(seed, (x as u32, y), index);
+                                sampler.start_pixel_sample((x as u32, y), index);
+                                let (offset_x, offset_y) = sampler.get_2d();
+                                let u = (y as f32 + offset_y) / (size.1 - 1) as f32;
+                                let v = (x as f32 + offset_x) / (size.0 - 1) as f32;
+
+                                let ray = camera.get_ray(u, v, sampler.as_mut());
+                                let mut sample = CameraSample { camera: &camera, index, sampler: sampler.as_mut(), splats: &mut splats };
+                                color = color + integrator.sample_radiance(&ray, &scene, &mut sample);
                             }
 
-                            let real_color = gamma_correction(color, samples);
-                            let real_color = float_to_u8_color(real_color);
-
-                            temp.push(Pixel::new(Point::new(x as i32, y as i32), Color::RGB(real_color.0,real_color.1,real_color.2)));
+                            temp.push((x as u32, color));
                         }
+                        let mut pixels: Vec<Pixel>;
+                        {
+                            let mut film = film.lock().unwrap();
+                            for &(x, color) in &temp {
+                                film.add_color(x, y, color, pass_samples);
+                            }
+                            film.add_splats(&mut splats);
+                            pixels = temp.iter().map(|&(x, _)| film.pixel(x, y)).collect();
+                        }
+                        temp.clear();
                         {
                             let mut queue = queue_mutex.lock().unwrap();
-                            queue.append(&mut temp);
+                            queue.append(&mut pixels);
+                        }
+                    }
+
+                    // Splats also land on lines that were shown already, so the finished film is
+                    // shown once more as a whole
+                    {
+                        let mut finished = lines_finished.lock().unwrap();
+                        *finished += 1;
+                        if *finished == size.1 {
+                            if pass + 1 < passes {
+                                *finished = 0;
+                                *lines_remaining.lock().unwrap() = (pass + 1, size.1);
+                            } else {
+                                let film = film.lock().unwrap();
+                                if film.has_splats() {
+                                    queue_mutex.lock().unwrap().append(&mut film.pixels());
+                                }
+                            }
                         }
                     }
                     // Outputting Process-Status
 
                     {
                         let remaining = lines_remaining.lock().unwrap();
-                        print!("\rRendering at: {}%", (100.0 - *remaining as f32 / size.1 as f32 * 100.0) as u8);
+                        let done = (remaining.0 + 1) as f32 - remaining.1 as f32 / size.1 as f32;
+                        print!("\rRendering at: {}%", (done / passes as f32 * 100.0) as u8);
                     }
 
 
@@ -157,4 +234,82 @@ impl Renderer {
     pub fn set_size(&mut self, size: (u32,u32)) {
         self.properties.size = size;
     }
-}
\ No newline at end of file
+
+    pub fn set_fog(&mut self, fog: Option<Fog>) {
+        self.properties.fog = fog;
+    }
+
+    pub fn set_shutter(&mut self, shutter_open: f32, shutter_close: f32) {
+        self.properties.shutter = (shutter_open, shutter_close);
+    }
+
+    pub fn set_integrator(&mut self, integrator: IntegratorKind) {
+        self.properties.integrator = integrator;
+    }
+
+    // Renders with the same seed and properties give the same image
+    pub fn set_seed(&mut self, seed: u64) {
+        self.properties.seed = seed;
+    }
+
+    pub fn set_sampler(&mut self, sampler: SamplerKind) {
+        self.properties.sampler = sampler;
+    }
+
+    pub fn set_samples(&mut self, samples: u32) {
+        self.properties.samples = samples;
+    }
+
+    // Lights the scene with a daylight sky and its sun instead of the plain sky
+    pub fn set_sky(&mut self, sky: Option<Sky>) {
+        self.properties.sky = sky;
+    }
+
+    // Lights the scene with the environment map instead of the plain sky
+    pub fn set_environment(&mut self, environment: Option<Arc<EnvironmentMap>>) {
+        self.environment = environment;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Final pixels of a small render, sorted by position
+    fn render(threads: u32, integrator: IntegratorKind) -> Vec<(i32, i32, u8, u8, u8)> {
+        let size = (64, 48);
+        let samples = 4;
+        let mut renderer = Renderer::new(RendererProperties::new(size, samples, 4, (threads as f32 + 0.5) / num_cpus::get() as f32));
+        renderer.set_integrator(integrator);
+        // Every number comes from the stream of the sample, not from a sequence over the pixel
+        renderer.set_sampler(SamplerKind::Independent);
+        renderer.set_seed(7);
+
+        // Rendering in passes shows every line once per pass
+        let shown = size.0 * size.1 * if integrator.renders_in_passes() { samples } else { 1 };
+        let (queue, handles, stop) = renderer.start_render();
+        let mut pixels = vec![None; (size.0 * size.1) as usize];
+        let mut received = 0;
+        while received < shown {
+            for pixel in queue.lock().unwrap().drain(..) {
+                let (x, y) = (pixel.position.x(), pixel.position.y());
+                pixels[(y * size.0 as i32 + x) as usize] = Some((x, y, pixel.color.r, pixel.color.g, pixel.color.b));
+                received += 1;
+            }
+            thread::yield_now();
+        }
+
+        *stop.lock().unwrap() = true;
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        pixels.into_iter().map(|pixel| pixel.unwrap()).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_image_on_any_number_of_threads() {
+        assert_eq!(render(1, IntegratorKind::PathTracer), render(4, IntegratorKind::PathTracer));
+
+        let photons = IntegratorKind::PhotonMapping { photons: 100, radius: 0.1 };
+        assert_eq!(render(1, photons), render(4, photons));
+    }
+}
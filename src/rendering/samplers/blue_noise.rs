@@ -0,0 +1,111 @@
+use std::sync::OnceLock;
+use crate::rendering::samplers::sampler::{hash, to_float, Sampler};
+
+// Side length of the tiled mask in pixels
+const MASK_SIZE: usize = 64;
+
+// Spread of the energy around each pixel of the mask while it's built
+const SIGMA: f32 = 1.5;
+
+// Fractional parts of the golden ratio and its 2D generalization, which spread the samples of a
+// pixel evenly when added up again and again
+const GOLDEN: f64 = 0.618_033_988_749_895;
+const PLASTIC: (f64, f64) = (0.754_877_666_246_693, 0.569_840_290_998_053);
+
+static MASK: OnceLock<Vec<f32>> = OnceLock::new();
+
+// Threshold mask of blue noise built by greedily filling voids: starting from an empty tile, pixels
+// are switched on one by one in the largest gap left between those already on, and numbered in
+// that order. Any threshold of the mask then gives evenly spread pixels without clumps. Unlike
+// full void and cluster (Ulichney 1993) there's no relaxed initial pattern and no removal of
+// clusters, which only evens out the first few pixels a little more.
+fn build_mask() -> Vec<f32> {
+    let pixels = MASK_SIZE * MASK_SIZE;
+
+    // Energy every pixel that's on adds to the others, wrapping around the edges of the tile
+    let mut kernel = vec![0.0; pixels];
+    for y in 0..MASK_SIZE {
+        for x in 0..MASK_SIZE {
+            let dx = x.min(MASK_SIZE - x) as f32;
+            let dy = y.min(MASK_SIZE - y) as f32;
+            kernel[y * MASK_SIZE + x] = (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp();
+        }
+    }
+
+    // A little noise decides between gaps of the same size, which would otherwise line up
+    let mut energy: Vec<f32> = (0..pixels as u32).map(|pixel| to_float(hash(&[pixel])) * 1e-3).collect();
+    let mut rank = vec![None; pixels];
+
+    for order in 0..pixels {
+        let mut void = 0;
+        let mut lowest = f32::INFINITY;
+        for (pixel, &value) in energy.iter().enumerate() {
+            if rank[pixel].is_none() && value < lowest {
+                lowest = value;
+                void = pixel;
+            }
+        }
+        rank[void] = Some(order);
+
+        let (vx, vy) = (void % MASK_SIZE, void / MASK_SIZE);
+        for y in 0..MASK_SIZE {
+            for x in 0..MASK_SIZE {
+                let offset = ((y + MASK_SIZE - vy) % MASK_SIZE) * MASK_SIZE + (x + MASK_SIZE - vx) % MASK_SIZE;
+                energy[y * MASK_SIZE + x] += kernel[offset];
+            }
+        }
+    }
+
+    rank.iter().map(|order| (order.unwrap_or(0) as f32 + 0.5) / pixels as f32).collect()
+}
+
+// Samples whose error looks like blue noise between neighbouring pixels, fine grained instead of
+// clumpy, which already pays off at one sample per pixel. Every dimension reads a tiled blue noise
+// mask at its own offset, the samples of a pixel then step through the golden ratio sequence from
+// there (Georgiev and Fajardo 2016, Heitz and Belcour 2019). Meant for previews with a few samples
+// per pixel, Sobol converges faster with many.
+pub struct BlueNoise {
+    seed: u32,
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32,
+}
+
+impl BlueNoise {
+    pub fn new(seed: u32) -> Self {
+        Self { seed, pixel: (0, 0), index: 0, dimension: 0 }
+    }
+
+    // Value of the mask at the pixel for the next dimension
+    fn mask(&mut self) -> f32 {
+        let mask = MASK.get_or_init(build_mask);
+        let offset = hash(&[self.seed, self.dimension]);
+        self.dimension += 1;
+
+        let x = (self.pixel.0 as usize + (offset & 0xffff) as usize) % MASK_SIZE;
+        let y = (self.pixel.1 as usize + (offset >> 16) as usize) % MASK_SIZE;
+        mask[y * MASK_SIZE + x]
+    }
+}
+
+fn wrap(value: f32) -> f32 {
+    (value - value.floor()).min(1.0 - f32::EPSILON)
+}
+
+impl Sampler for BlueNoise {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let step = (self.index as f64 * GOLDEN).fract() as f32;
+        wrap(self.mask() + step)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let step = ((self.index as f64 * PLASTIC.0).fract() as f32, (self.index as f64 * PLASTIC.1).fract() as f32);
+        (wrap(self.mask() + step.0), wrap(self.mask() + step.1))
+    }
+}
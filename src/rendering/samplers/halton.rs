@@ -0,0 +1,64 @@
+use crate::rendering::samplers::sampler::{hash, permute, to_float, Sampler};
+
+// Bases of the dimensions, larger primes spread their first points worse so the sequence stops
+// after these and further dimensions get plain random numbers
+const PRIMES: [u32; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53,
+    59, 61, 67, 71, 73, 79, 83, 89, 97, 101, 103, 107, 109, 113, 127, 131,
+];
+
+// Mirrors the digits of the index in the base around the decimal point. Every digit position gets
+// its own random permutation of the digits picked by the seed, including the zeros in front of
+// the index, until the digits are too small to matter.
+fn scrambled_radical_inverse(base: u32, mut index: u32, seed: u32) -> f32 {
+    let inverse_base = 1.0 / base as f64;
+    let mut factor = inverse_base;
+    let mut value = 0.0;
+    let mut position = 0;
+    while factor > 1e-9 {
+        let digit = permute(index % base, base, hash(&[seed, position]));
+        value += digit as f64 * factor;
+        index /= base;
+        factor *= inverse_base;
+        position += 1;
+    }
+    value as f32
+}
+
+// Halton sequence with a prime base per dimension. All pixels walk the same sequence with their
+// own random digit scrambling per dimension, which also breaks up the patterns that larger bases
+// show between neighbouring dimensions.
+pub struct Halton {
+    seed: u32,
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32,
+}
+
+impl Halton {
+    pub fn new(seed: u32) -> Self {
+        Self { seed, pixel: (0, 0), index: 0, dimension: 0 }
+    }
+}
+
+impl Sampler for Halton {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let seed = hash(&[self.seed, self.pixel.0, self.pixel.1, self.dimension]);
+        let value = match PRIMES.get(self.dimension as usize) {
+            Some(&base) => scrambled_radical_inverse(base, self.index, seed),
+            None => to_float(hash(&[seed, self.index])),
+        };
+        self.dimension += 1;
+        value.min(1.0 - f32::EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        (self.get_1d(), self.get_1d())
+    }
+}
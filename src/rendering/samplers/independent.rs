@@ -0,0 +1,20 @@
+use rand::Rng;
+use crate::rendering::random;
+use crate::rendering::samplers::sampler::Sampler;
+
+// Fresh uniform random numbers for every dimension, without any spreading between samples. They
+// come from the random numbers of the current sample, so they follow the seed of the render.
+pub struct Independent;
+
+impl Sampler for Independent {
+    fn start_pixel_sample(&mut self, _pixel: (u32, u32), _index: u32) {}
+
+    fn get_1d(&mut self) -> f32 {
+        random::rng().gen()
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let mut rng = random::rng();
+        (rng.gen(), rng.gen())
+    }
+}
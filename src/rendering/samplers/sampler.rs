@@ -0,0 +1,109 @@
+use crate::rendering::samplers::blue_noise::BlueNoise;
+use crate::rendering::samplers::halton::Halton;
+use crate::rendering::samplers::independent::Independent;
+use crate::rendering::samplers::sobol::Sobol;
+use crate::rendering::samplers::stratified::Stratified;
+
+// Hands out the random numbers of one sample of a pixel. Every call moves on to the next
+// dimension, so the same decision along a path, like the pixel position or the direction of the
+// first bounce, gets the same dimension in every sample of the pixel and the samples can be
+// spread evenly over it. All numbers are between 0 and 1, excluding 1.
+pub trait Sampler {
+    // Starts the sample with the given index of the pixel at its first dimension
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32);
+
+    fn get_1d(&mut self) -> f32;
+
+    fn get_2d(&mut self) -> (f32, f32);
+}
+
+pub enum SamplerKind {
+    Independent,
+    // Splits every dimension into as many strata as there are samples per pixel
+    Stratified,
+    Halton,
+    // Sobol points with Owen scrambling, different for every pixel and dimension
+    Sobol,
+    // Spreads the error as blue noise over the image, for previews with few samples per pixel
+    BlueNoise,
+}
+
+impl SamplerKind {
+    // Samplers with the same seed hand out the same numbers for the same pixel sample
+    pub fn create(&self, samples: u32, seed: u64) -> Box<dyn Sampler> {
+        let seed = hash(&[seed as u32, (seed >> 32) as u32]);
+        match *self {
+            SamplerKind::Independent => Box::new(Independent),
+            SamplerKind::Stratified => Box::new(Stratified::new(samples, seed)),
+            SamplerKind::Halton => Box::new(Halton::new(seed)),
+            SamplerKind::Sobol => Box::new(Sobol::new(seed)),
+            SamplerKind::BlueNoise => Box::new(BlueNoise::new(seed)),
+        }
+    }
+}
+
+impl Clone for SamplerKind {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for SamplerKind {}
+
+// Mixes the values into one well distributed number, the same for the same values
+pub fn hash(values: &[u32]) -> u32 {
+    let mut hash = 0x9e37_79b9_u32;
+    for &value in values {
+        hash ^= value.wrapping_add(0x9e37_79b9).wrapping_add(hash << 6).wrapping_add(hash >> 2);
+
+        // Finalizer with a low bias (Wellons 2018)
+        hash ^= hash >> 16;
+        hash = hash.wrapping_mul(0x7feb_352d);
+        hash ^= hash >> 15;
+        hash = hash.wrapping_mul(0x846c_a68b);
+        hash ^= hash >> 16;
+    }
+    hash
+}
+
+// Number between 0 and 1 from the upper bits, which can't be rounded up to 1
+pub fn to_float(bits: u32) -> f32 {
+    (bits >> 8) as f32 / (1 << 24) as f32
+}
+
+// Position of index in a random permutation of 0..length, picked by the seed (Kensler 2013)
+pub fn permute(index: u32, length: u32, seed: u32) -> u32 {
+    let mut mask = length - 1;
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+    mask |= mask >> 16;
+
+    // Permutes the next power of two and walks on until the result is in range
+    let mut i = index;
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170_893d);
+        i ^= seed >> 16;
+        i ^= (i & mask) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929_eb3f);
+        i ^= seed >> 23;
+        i ^= (i & mask) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935_fa69);
+        i ^= (i & mask) >> 11;
+        i = i.wrapping_mul(0x74dc_b303);
+        i ^= (i & mask) >> 2;
+        i = i.wrapping_mul(0x9e50_1cc3);
+        i ^= (i & mask) >> 2;
+        i = i.wrapping_mul(0xc860_a3df);
+        i &= mask;
+        i ^= i >> 5;
+        if i < length {
+            break;
+        }
+    }
+    i.wrapping_add(seed) % length
+}
@@ -0,0 +1,69 @@
+use crate::rendering::samplers::sampler::{hash, to_float, Sampler};
+
+// First two dimensions of the Sobol sequence, as fixed point fractions. The first mirrors the
+// bits of the index, the second combines them with the direction numbers of Pascal's triangle.
+fn sobol(index: u32) -> (u32, u32) {
+    let mut y = 0;
+    let mut direction = 1 << 31;
+    for bit in 0..32 {
+        if (index >> bit) & 1 == 1 {
+            y ^= direction;
+        }
+        direction ^= direction >> 1;
+    }
+    (index.reverse_bits(), y)
+}
+
+// Randomly flips subtrees of the bits, which keeps how well the points of a Sobol sequence are
+// spread out (Owen scrambling as a hash, Laine and Karras 2011, Burley 2020)
+fn owen_scramble(value: u32, seed: u32) -> u32 {
+    let mut x = value.reverse_bits();
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x.reverse_bits()
+}
+
+// Scrambled Sobol points. Every dimension (or pair of them) uses the first two dimensions of the
+// sequence with its own scrambling, and its own shuffle of the sample order so the dimensions
+// don't correlate with each other (Burley 2020). Works best with a power of two samples per pixel.
+pub struct Sobol {
+    seed: u32,
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32,
+}
+
+impl Sobol {
+    pub fn new(seed: u32) -> Self {
+        Self { seed, pixel: (0, 0), index: 0, dimension: 0 }
+    }
+
+    // Scrambled point of the current sample for the next dimension
+    fn point(&mut self) -> (u32, u32) {
+        let seed = hash(&[self.seed, self.pixel.0, self.pixel.1, self.dimension]);
+        self.dimension += 1;
+
+        let (x, y) = sobol(owen_scramble(self.index, seed));
+        (owen_scramble(x, hash(&[seed, 0])), owen_scramble(y, hash(&[seed, 1])))
+    }
+}
+
+impl Sampler for Sobol {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        to_float(self.point().0)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let (x, y) = self.point();
+        (to_float(x), to_float(y))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::rendering::samplers::sampler::{hash, permute, to_float, Sampler};
+
+// Jittered sampling: every dimension is cut into one stratum per sample of the pixel, 2D samples
+// into a grid of about as many cells. Each sample lands in its own stratum at a random offset.
+// The strata are shuffled separately for every dimension, so dimensions don't correlate.
+pub struct Stratified {
+    samples: u32,
+    seed: u32,
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32,
+}
+
+impl Stratified {
+    pub fn new(samples: u32, seed: u32) -> Self {
+        Self { samples: samples.max(1), seed, pixel: (0, 0), index: 0, dimension: 0 }
+    }
+
+    // Stratum of the current sample among the given count, and a seed for its jitter. Samples
+    // past the count start over with another shuffle.
+    fn stratum(&mut self, strata: u32) -> (u32, u32) {
+        let round = self.index / self.samples;
+        let seed = hash(&[self.seed, self.pixel.0, self.pixel.1, self.dimension, round]);
+        self.dimension += 1;
+        (permute(self.index % self.samples, strata, seed), hash(&[seed, self.index]))
+    }
+}
+
+impl Sampler for Stratified {
+    fn start_pixel_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let (stratum, jitter) = self.stratum(self.samples);
+        ((stratum as f32 + to_float(jitter)) / self.samples as f32).min(1.0 - f32::EPSILON)
+    }
+
+    // Sample counts that aren't a square leave some cells of the grid out, which cells those are
+    // changes with the shuffle
+    fn get_2d(&mut self) -> (f32, f32) {
+        let columns = (self.samples as f32).sqrt().ceil() as u32;
+        let rows = self.samples.div_ceil(columns);
+        let (stratum, jitter) = self.stratum(columns * rows);
+
+        let x = (stratum % columns) as f32 + to_float(jitter);
+        let y = (stratum / columns) as f32 + to_float(hash(&[jitter]));
+        ((x / columns as f32).min(1.0 - f32::EPSILON), (y / rows as f32).min(1.0 - f32::EPSILON))
+    }
+}
@@ -0,0 +1,71 @@
+use crate::rendering::lights::light::{Light, SceneBounds};
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+// Everything an integrator needs to know about the world it renders
+pub struct Scene {
+    world: Box<dyn Hittable>,
+    pub lights: Vec<Box<dyn Light>>,
+    environment: Option<usize>,
+}
+
+impl Scene {
+    pub fn new(world: Box<dyn Hittable>) -> Self {
+        Self { world, lights: Vec::new(), environment: None }
+    }
+
+    pub fn add_light(&mut self, mut light: Box<dyn Light>) {
+        if let Some(bounds) = self.world.bounding_box() {
+            light.set_scene_bounds(SceneBounds {
+                center: (bounds.minimum + bounds.maximum) / 2.0,
+                radius: (bounds.maximum - bounds.minimum).length() / 2.0,
+            });
+        }
+        self.lights.push(light);
+    }
+
+    // Infinite light that replaces the sky, it's seen by rays leaving the scene and sampled like
+    // any other light
+    pub fn set_environment(&mut self, light: Box<dyn Light>) {
+        self.add_light(light);
+        match self.environment {
+            Some(index) => {
+                self.lights.swap_remove(index);
+            }
+            None => self.environment = Some(self.lights.len() - 1),
+        }
+    }
+
+    // Index of the environment light, if the sky is one
+    pub fn environment(&self) -> Option<usize> {
+        self.environment
+    }
+
+    // Density with which sample_direct_light picks direction towards the light at index, lights are
+    // chosen uniformly. Other lights along the direction are hidden behind it and don't count.
+    pub fn light_pdf(&self, index: usize, origin: Vec3, direction: Vec3) -> f32 {
+        self.lights[index].pdf(origin, direction) / self.lights.len() as f32
+    }
+
+    // Index of the light whose surface lies at position. Delta and infinite lights can't be hit.
+    pub fn light_at(&self, position: Vec3, direction: Vec3) -> Option<usize> {
+        self.lights.iter().position(|light| {
+            !light.is_delta() && !light.is_infinite() && light.emission_pdf(position, direction).0 > 0.0
+        })
+    }
+
+    pub fn hit<'a>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'a>) -> bool {
+        self.world.hit(ray, t_min, t_max, record)
+    }
+
+    pub fn background(&self, ray: &Ray) -> Vec3 {
+        if let Some(index) = self.environment {
+            return self.lights[index].radiance(ray.direction().unit());
+        }
+
+        let unit_vector = ray.direction().unit();
+        let t = 0.5 * (unit_vector.1 + 1.0);
+        Vec3(1.0, 1.0, 1.0) * (1.0 - t) + Vec3(0.5, 0.7, 1.0) * t
+    }
+}
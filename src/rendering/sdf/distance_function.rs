@@ -0,0 +1,7 @@
+use crate::rendering::vector_math::Vec3;
+
+// Returns the signed distance to the closest surface, negative inside of the shape. Functions that
+// overestimate the distance (like twisted shapes) need a reduced step scale on the SdfObject.
+pub trait DistanceFunction {
+    fn distance(&self, point: Vec3) -> f32;
+}
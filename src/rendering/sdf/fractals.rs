@@ -0,0 +1,88 @@
+use crate::rendering::sdf::distance_function::DistanceFunction;
+use crate::rendering::vector_math::Vec3;
+
+pub struct Mandelbulb {
+    center: Vec3,
+    scale: f32,
+    power: f32,
+    iterations: u32,
+}
+
+impl Mandelbulb {
+    // The unscaled bulb fits into a sphere of radius 1.2 for the classic power of 8
+    pub fn new(center: Vec3, scale: f32, power: f32, iterations: u32) -> Self {
+        Self { center, scale, power, iterations }
+    }
+}
+
+impl DistanceFunction for Mandelbulb {
+    fn distance(&self, point: Vec3) -> f32 {
+        let p = (point - self.center) / self.scale;
+
+        let mut z = p;
+        let mut derivative = 1.0;
+        let mut radius = z.length();
+
+        for _ in 0..self.iterations {
+            if radius > 2.0 {
+                break;
+            }
+
+            let theta = (z.2 / radius).clamp(-1.0, 1.0).acos() * self.power;
+            let phi = z.1.atan2(z.0) * self.power;
+            derivative = radius.powf(self.power - 1.0) * self.power * derivative + 1.0;
+
+            let scaled = radius.powf(self.power);
+            z = Vec3(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos()) * scaled + p;
+            radius = z.length();
+        }
+
+        0.5 * radius.ln() * radius / derivative * self.scale
+    }
+}
+
+pub struct MengerSponge {
+    center: Vec3,
+    half_size: f32,
+    iterations: u32,
+}
+
+impl MengerSponge {
+    pub fn new(center: Vec3, half_size: f32, iterations: u32) -> Self {
+        Self { center, half_size, iterations }
+    }
+}
+
+impl DistanceFunction for MengerSponge {
+    fn distance(&self, point: Vec3) -> f32 {
+        let p = (point - self.center) / self.half_size;
+
+        let q = Vec3(p.0.abs(), p.1.abs(), p.2.abs()) + -1.0;
+        let mut distance = q.max(&Vec3(0.0, 0.0, 0.0)).length() + q.0.max(q.1.max(q.2)).min(0.0);
+
+        // Every iteration carves a cross shaped hole into each of the remaining sub cubes
+        let mut scale = 1.0;
+        for _ in 0..self.iterations {
+            let a = Vec3(
+                (p.0 * scale).rem_euclid(2.0) - 1.0,
+                (p.1 * scale).rem_euclid(2.0) - 1.0,
+                (p.2 * scale).rem_euclid(2.0) - 1.0,
+            );
+            scale *= 3.0;
+
+            let r = Vec3(
+                (1.0 - 3.0 * a.0.abs()).abs(),
+                (1.0 - 3.0 * a.1.abs()).abs(),
+                (1.0 - 3.0 * a.2.abs()).abs(),
+            );
+            let da = r.0.max(r.1);
+            let db = r.1.max(r.2);
+            let dc = r.2.max(r.0);
+            let cross = (da.min(db.min(dc)) - 1.0) / scale;
+
+            distance = distance.max(cross);
+        }
+
+        distance * self.half_size
+    }
+}
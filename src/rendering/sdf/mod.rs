@@ -0,0 +1,4 @@
+pub mod distance_function;
+pub mod primitives;
+pub mod operations;
+pub mod fractals;
@@ -0,0 +1,130 @@
+use crate::rendering::sdf::distance_function::DistanceFunction;
+use crate::rendering::vector_math::Vec3;
+
+pub struct Union {
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+}
+
+impl Union {
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>) -> Self {
+        Self { a, b }
+    }
+}
+
+impl DistanceFunction for Union {
+    fn distance(&self, point: Vec3) -> f32 {
+        self.a.distance(point).min(self.b.distance(point))
+    }
+}
+
+pub struct Subtraction {
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+}
+
+impl Subtraction {
+    // Removes b from a
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>) -> Self {
+        Self { a, b }
+    }
+}
+
+impl DistanceFunction for Subtraction {
+    fn distance(&self, point: Vec3) -> f32 {
+        self.a.distance(point).max(-self.b.distance(point))
+    }
+}
+
+pub struct Intersection {
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+}
+
+impl Intersection {
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>) -> Self {
+        Self { a, b }
+    }
+}
+
+impl DistanceFunction for Intersection {
+    fn distance(&self, point: Vec3) -> f32 {
+        self.a.distance(point).max(self.b.distance(point))
+    }
+}
+
+pub struct SmoothUnion {
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+    smoothness: f32,
+}
+
+impl SmoothUnion {
+    // The smoothness is roughly the distance over which both shapes blend into each other
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>, smoothness: f32) -> Self {
+        Self { a, b, smoothness }
+    }
+}
+
+impl DistanceFunction for SmoothUnion {
+    fn distance(&self, point: Vec3) -> f32 {
+        let a = self.a.distance(point);
+        let b = self.b.distance(point);
+        if self.smoothness <= 0.0 {
+            return a.min(b);
+        }
+
+        let h = (0.5 + 0.5 * (b - a) / self.smoothness).clamp(0.0, 1.0);
+        b + (a - b) * h - self.smoothness * h * (1.0 - h)
+    }
+}
+
+pub struct Repetition {
+    inner: Box<dyn DistanceFunction>,
+    period: Vec3,
+}
+
+impl Repetition {
+    // Repeats the cell around the origin on every axis with a non zero period
+    pub fn new(inner: Box<dyn DistanceFunction>, period: Vec3) -> Self {
+        Self { inner, period }
+    }
+}
+
+fn repeat(value: f32, period: f32) -> f32 {
+    if period <= 0.0 {
+        return value;
+    }
+    (value + 0.5 * period).rem_euclid(period) - 0.5 * period
+}
+
+impl DistanceFunction for Repetition {
+    fn distance(&self, point: Vec3) -> f32 {
+        self.inner.distance(Vec3(
+            repeat(point.0, self.period.0),
+            repeat(point.1, self.period.1),
+            repeat(point.2, self.period.2),
+        ))
+    }
+}
+
+pub struct Twist {
+    inner: Box<dyn DistanceFunction>,
+    rate: f32,
+}
+
+impl Twist {
+    // Rotates the shape around the y axis by rate radians per unit of height
+    pub fn new(inner: Box<dyn DistanceFunction>, rate: f32) -> Self {
+        Self { inner, rate }
+    }
+}
+
+impl DistanceFunction for Twist {
+    fn distance(&self, point: Vec3) -> f32 {
+        let angle = self.rate * point.1;
+        let (sin, cos) = angle.sin_cos();
+
+        self.inner.distance(Vec3(cos * point.0 - sin * point.2, point.1, sin * point.0 + cos * point.2))
+    }
+}
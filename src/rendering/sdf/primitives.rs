@@ -0,0 +1,81 @@
+use crate::rendering::sdf::distance_function::DistanceFunction;
+use crate::rendering::vector_math::Vec3;
+
+pub struct SdfSphere {
+    center: Vec3,
+    radius: f32,
+}
+
+impl SdfSphere {
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+}
+
+impl DistanceFunction for SdfSphere {
+    fn distance(&self, point: Vec3) -> f32 {
+        (point - self.center).length() - self.radius
+    }
+}
+
+pub struct SdfBox {
+    center: Vec3,
+    half_size: Vec3,
+    rounding: f32,
+}
+
+impl SdfBox {
+    // The rounding radius is taken from the half size, so the outer dimensions stay the same
+    pub fn new(center: Vec3, half_size: Vec3, rounding: f32) -> Self {
+        Self { center, half_size, rounding }
+    }
+}
+
+impl DistanceFunction for SdfBox {
+    fn distance(&self, point: Vec3) -> f32 {
+        let p = point - self.center;
+        let q = Vec3(p.0.abs(), p.1.abs(), p.2.abs()) - self.half_size + self.rounding;
+
+        q.max(&Vec3(0.0, 0.0, 0.0)).length() + q.0.max(q.1.max(q.2)).min(0.0) - self.rounding
+    }
+}
+
+pub struct SdfTorus {
+    center: Vec3,
+    major_radius: f32,
+    minor_radius: f32,
+}
+
+impl SdfTorus {
+    // The ring lies in the xz plane
+    pub fn new(center: Vec3, major_radius: f32, minor_radius: f32) -> Self {
+        Self { center, major_radius, minor_radius }
+    }
+}
+
+impl DistanceFunction for SdfTorus {
+    fn distance(&self, point: Vec3) -> f32 {
+        let p = point - self.center;
+        let ring = (p.0 * p.0 + p.2 * p.2).sqrt() - self.major_radius;
+
+        (ring * ring + p.1 * p.1).sqrt() - self.minor_radius
+    }
+}
+
+pub struct SdfPlane {
+    normal: Vec3,
+    offset: f32,
+}
+
+impl SdfPlane {
+    // Everything below the plane through normal * offset counts as inside
+    pub fn new(normal: Vec3, offset: f32) -> Self {
+        Self { normal: normal.unit(), offset }
+    }
+}
+
+impl DistanceFunction for SdfPlane {
+    fn distance(&self, point: Vec3) -> f32 {
+        point * self.normal - self.offset
+    }
+}
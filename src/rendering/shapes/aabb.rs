@@ -0,0 +1,49 @@
+use crate::rendering::ray::Ray;
+use crate::rendering::vector_math::Vec3;
+
+pub struct Aabb {
+    pub minimum: Vec3,
+    pub maximum: Vec3,
+}
+
+impl Aabb {
+    pub fn new(minimum: Vec3, maximum: Vec3) -> Self {
+        Self { minimum, maximum }
+    }
+
+    pub fn surrounding(a: &Aabb, b: &Aabb) -> Aabb {
+        Aabb::new(a.minimum.min(&b.minimum), a.maximum.max(&b.maximum))
+    }
+
+    pub fn hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> bool {
+        self.intersect(ray, t_min, t_max).is_some()
+    }
+
+    // The range of the ray that lies inside of the box
+    pub fn intersect(&self, ray: &Ray, mut t_min: f32, mut t_max: f32) -> Option<(f32, f32)> {
+        for axis in 0..3 {
+            let inverse = 1.0 / ray.direction().axis(axis);
+            let mut t0 = (self.minimum.axis(axis) - ray.origin().axis(axis)) * inverse;
+            let mut t1 = (self.maximum.axis(axis) - ray.origin().axis(axis)) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_max <= t_min {
+                return None;
+            }
+        }
+
+        Some((t_min, t_max))
+    }
+}
+
+impl Clone for Aabb {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for Aabb {}
@@ -0,0 +1,151 @@
+use std::cell::Cell;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+
+thread_local! {
+    // Bounding boxes the hierarchies tested on this thread, for visualizing the traversal cost.
+    // Only counted inside of count_box_tests.
+    static BOX_TESTS: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+// Runs the closure and returns how many bounding boxes it tested on this thread
+pub fn count_box_tests<T>(f: impl FnOnce() -> T) -> (T, u32) {
+    BOX_TESTS.with(|tests| tests.set(Some(0)));
+    let result = f();
+    let count = BOX_TESTS.with(|tests| tests.take()).unwrap_or(0);
+    (result, count)
+}
+
+enum BvhNode {
+    Leaf {
+        object: Box<dyn Hittable>,
+        bounds: Aabb,
+        index: u32,
+    },
+    Branch {
+        left: Box<BvhNode>,
+        right: Box<BvhNode>,
+        bounds: Aabb,
+    },
+}
+
+impl BvhNode {
+    fn build(mut objects: Vec<(Box<dyn Hittable>, Aabb, u32)>) -> BvhNode {
+        if objects.len() == 1 {
+            let (object, bounds, index) = objects.pop().unwrap();
+            return BvhNode::Leaf { object, bounds, index };
+        }
+
+        // Split at the median along the axis where the centers are spread the most
+        let center = |bounds: &Aabb| (bounds.minimum + bounds.maximum) / 2.0;
+        let first = center(&objects[0].1);
+        let (low, high) = objects.iter()
+            .fold((first, first), |(low, high), (_, bounds, _)| (low.min(&center(bounds)), high.max(&center(bounds))));
+        let extent = high - low;
+        let axis = if extent.0 > extent.1 && extent.0 > extent.2 { 0 } else if extent.1 > extent.2 { 1 } else { 2 };
+
+        objects.sort_by(|a, b| center(&a.1).axis(axis).total_cmp(&center(&b.1).axis(axis)));
+        let right = objects.split_off(objects.len() / 2);
+
+        let left = BvhNode::build(objects);
+        let right = BvhNode::build(right);
+        let bounds = Aabb::surrounding(left.bounds(), right.bounds());
+
+        BvhNode::Branch { left: Box::new(left), right: Box::new(right), bounds }
+    }
+
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bounds, .. } => bounds,
+            BvhNode::Branch { bounds, .. } => bounds,
+        }
+    }
+
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        BOX_TESTS.with(|tests| {
+            if let Some(count) = tests.get() {
+                tests.set(Some(count.saturating_add(1)));
+            }
+        });
+        if !self.bounds().hit(ray, t_min, t_max) {
+            return false;
+        }
+
+        match self {
+            BvhNode::Leaf { object, index, .. } => {
+                let hit = object.hit(ray, t_min, t_max, record);
+                if hit {
+                    record.object_id = *index;
+                }
+                hit
+            }
+            BvhNode::Branch { left, right, .. } => {
+                let hit_left = left.hit(ray, t_min, t_max, record);
+                let closest = if hit_left { record.t } else { t_max };
+                let hit_right = right.hit(ray, t_min, closest, record);
+
+                hit_left || hit_right
+            }
+        }
+    }
+}
+
+// Bounding volume hierarchy over a set of objects. Objects without bounds (like infinite
+// repetitions) can't be sorted into the tree and are tested on every ray. Hits report the index
+// of the object in the list the hierarchy was built from.
+pub struct Bvh {
+    root: Option<BvhNode>,
+    unbounded: Vec<(Box<dyn Hittable>, u32)>,
+}
+
+impl Bvh {
+    pub fn new(objects: Vec<Box<dyn Hittable>>) -> Self {
+        let mut bounded = Vec::new();
+        let mut unbounded = Vec::new();
+
+        for (index, object) in objects.into_iter().enumerate() {
+            let index = index as u32;
+            match object.bounding_box() {
+                Some(bounds) => bounded.push((object, bounds, index)),
+                None => unbounded.push((object, index)),
+            }
+        }
+
+        let root = if bounded.is_empty() { None } else { Some(BvhNode::build(bounded)) };
+
+        Self { root, unbounded }
+    }
+}
+
+impl Hittable for Bvh {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let mut hit_anything = false;
+        let mut closest = t_max;
+
+        if let Some(root) = &self.root {
+            if root.hit(ray, t_min, closest, record) {
+                hit_anything = true;
+                closest = record.t;
+            }
+        }
+
+        for (object, index) in &self.unbounded {
+            if object.hit(ray, t_min, closest, record) {
+                hit_anything = true;
+                closest = record.t;
+                record.object_id = *index;
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        if !self.unbounded.is_empty() {
+            return None;
+        }
+
+        self.root.as_ref().map(|root| *root.bounds())
+    }
+}
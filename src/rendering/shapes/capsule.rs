@@ -0,0 +1,144 @@
+use std::f32::consts::PI;
+use crate::rendering::materials::material::Material;
+use crate::rendering::onb::Onb;
+use crate::rendering::polynomial::solve_quadratic;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+pub struct Capsule {
+    base: Vec3,
+    top: Vec3,
+    radius: f32,
+    height: f32,
+    frame: Onb,
+    material: Box<dyn Material>,
+}
+
+impl Capsule {
+    pub fn new(base: Vec3, top: Vec3, radius: f32, material: Box<dyn Material>) -> Self {
+        let height = (top - base).length();
+        let frame = Onb::new(top - base);
+        Self { base, top, radius, height, frame, material }
+    }
+}
+
+impl Hittable for Capsule {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        // Work in a frame where the segment runs from the origin along +z
+        let origin = self.frame.to_local(ray.origin() - self.base);
+        let direction = self.frame.to_local(ray.direction());
+
+        let mut nearest: Option<f32> = None;
+        let mut consider = |t: f32| {
+            if t >= t_min && t <= t_max && nearest.is_none_or(|closest| t < closest) {
+                nearest = Some(t);
+            }
+        };
+
+        let a = direction.0 * direction.0 + direction.1 * direction.1;
+        let b = 2.0 * (origin.0 * direction.0 + origin.1 * direction.1);
+        let c = origin.0 * origin.0 + origin.1 * origin.1 - self.radius * self.radius;
+        for root in solve_quadratic(a as f64, b as f64, c as f64) {
+            let t = root as f32;
+            let z = origin.2 + direction.2 * t;
+            if z >= 0.0 && z <= self.height {
+                consider(t);
+            }
+        }
+
+        // The hemispheres only count where they stick out past the ends of the segment
+        for center in [Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, self.height)] {
+            let oc = origin - center;
+            let a = direction.length_squared();
+            let b = 2.0 * (oc * direction);
+            let c = oc.length_squared() - self.radius * self.radius;
+            for root in solve_quadratic(a as f64, b as f64, c as f64) {
+                let t = root as f32;
+                let z = origin.2 + direction.2 * t;
+                if (center.2 == 0.0 && z < 0.0) || (center.2 > 0.0 && z > self.height) {
+                    consider(t);
+                }
+            }
+        }
+
+        let t = match nearest {
+            Some(t) => t,
+            None => return false,
+        };
+
+        // The normal points away from the closest point on the segment
+        let local_position = origin + direction * t;
+        let closest = Vec3(0.0, 0.0, local_position.2.clamp(0.0, self.height));
+        let local_normal = (local_position - closest) / self.radius;
+
+        record.t = t;
+        record.position = ray.at(t);
+        record.set_face_normal(ray, self.frame.local(local_normal));
+        record.u = (local_position.1.atan2(local_position.0) + PI) / (2.0 * PI);
+        record.v = ((local_position.2 + self.radius) / (self.height + 2.0 * self.radius)).clamp(0.0, 1.0);
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3(self.radius, self.radius, self.radius);
+        Some(Aabb::new(self.base.min(&self.top) - extent, self.base.max(&self.top) + extent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::shapes::testing::{close, gray, same, trace};
+
+    fn capsule() -> Capsule {
+        Capsule::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, 2.0), 1.0, gray())
+    }
+
+    #[test]
+    fn hits_the_side() {
+        let capsule = capsule();
+        let record = trace(&capsule, Vec3(0.0, -3.0, 1.0), Vec3(0.0, 1.0, 0.0), 0.001).unwrap();
+        assert!(close(record.t, 2.0));
+        assert!(same(record.normal, Vec3(0.0, -1.0, 0.0)));
+        assert!(record.font_face);
+        assert!(close(record.u, 0.25));
+        assert!(close(record.v, 0.5));
+    }
+
+    #[test]
+    fn hits_the_caps() {
+        let capsule = capsule();
+        let top = trace(&capsule, Vec3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(top.t, 2.0));
+        assert!(same(top.normal, Vec3(0.0, 0.0, 1.0)));
+        assert!(close(top.v, 1.0));
+
+        let bottom = trace(&capsule, Vec3(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0), 0.001).unwrap();
+        assert!(close(bottom.t, 4.0));
+        assert!(same(bottom.normal, Vec3(0.0, 0.0, -1.0)));
+        assert!(close(bottom.v, 0.0));
+
+        let off_axis = trace(&capsule, Vec3(0.0, 0.6, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(off_axis.t, 2.2));
+        assert!(same(off_axis.normal, Vec3(0.0, 0.6, 0.8)));
+        assert!(close(off_axis.u, 0.75));
+    }
+
+    #[test]
+    fn misses_past_the_caps() {
+        let capsule = capsule();
+        assert!(trace(&capsule, Vec3(0.0, -3.0, 3.5), Vec3(0.0, 1.0, 0.0), 0.001).is_none());
+        assert!(trace(&capsule, Vec3(0.9, -3.0, 2.9), Vec3(0.0, 1.0, 0.0), 0.001).is_none());
+    }
+
+    #[test]
+    fn bounds_the_capsule() {
+        let bounds = capsule().bounding_box().unwrap();
+        assert!(same(bounds.minimum, Vec3(-1.0, -1.0, -1.0)));
+        assert!(same(bounds.maximum, Vec3(1.0, 1.0, 3.0)));
+    }
+}
@@ -0,0 +1,153 @@
+use std::f32::consts::PI;
+use crate::rendering::materials::material::Material;
+use crate::rendering::onb::Onb;
+use crate::rendering::polynomial::solve_quadratic;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+pub struct Cone {
+    base: Vec3,
+    top: Vec3,
+    base_radius: f32,
+    top_radius: f32,
+    height: f32,
+    frame: Onb,
+    material: Box<dyn Material>,
+}
+
+impl Cone {
+    // A top_radius of zero gives a pointed cone, anything else a capped frustum
+    pub fn new(base: Vec3, base_radius: f32, top: Vec3, top_radius: f32, material: Box<dyn Material>) -> Self {
+        let height = (top - base).length();
+        let frame = Onb::new(top - base);
+        Self { base, top, base_radius, top_radius, height, frame, material }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        // Work in a frame where the cone axis is +z and the base cap sits at the origin
+        let origin = self.frame.to_local(ray.origin() - self.base);
+        let direction = self.frame.to_local(ray.direction());
+
+        let mut nearest: Option<(f32, Vec3)> = None;
+        let mut consider = |t: f32, normal: Vec3| {
+            if t >= t_min && t <= t_max && nearest.is_none_or(|(closest, _)| t < closest) {
+                nearest = Some((t, normal));
+            }
+        };
+
+        // The radius changes linearly along the axis: r(z) = base_radius + slope * z
+        let slope = (self.top_radius - self.base_radius) / self.height;
+        let radius_at_origin = self.base_radius + slope * origin.2;
+
+        let a = direction.0 * direction.0 + direction.1 * direction.1 - slope * slope * direction.2 * direction.2;
+        let b = 2.0 * (origin.0 * direction.0 + origin.1 * direction.1 - slope * direction.2 * radius_at_origin);
+        let c = origin.0 * origin.0 + origin.1 * origin.1 - radius_at_origin * radius_at_origin;
+        for root in solve_quadratic(a as f64, b as f64, c as f64) {
+            let t = root as f32;
+            let p = origin + direction * t;
+            if p.2 >= 0.0 && p.2 <= self.height {
+                let radius = self.base_radius + slope * p.2;
+                consider(t, Vec3(p.0, p.1, -slope * radius).unit());
+            }
+        }
+
+        if direction.2 != 0.0 {
+            let caps = [
+                (0.0, self.base_radius, Vec3(0.0, 0.0, -1.0)),
+                (self.height, self.top_radius, Vec3(0.0, 0.0, 1.0)),
+            ];
+            for (z, radius, normal) in caps {
+                let t = (z - origin.2) / direction.2;
+                let p = origin + direction * t;
+                if p.0 * p.0 + p.1 * p.1 <= radius * radius {
+                    consider(t, normal);
+                }
+            }
+        }
+
+        let (t, local_normal) = match nearest {
+            Some(nearest) => nearest,
+            None => return false,
+        };
+
+        let local_position = origin + direction * t;
+
+        record.t = t;
+        record.position = ray.at(t);
+        record.set_face_normal(ray, self.frame.local(local_normal));
+        record.u = (local_position.1.atan2(local_position.0) + PI) / (2.0 * PI);
+        record.v = (local_position.2 / self.height).clamp(0.0, 1.0);
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = self.frame.w;
+        let disc = Vec3(
+            (1.0 - axis.0 * axis.0).max(0.0).sqrt(),
+            (1.0 - axis.1 * axis.1).max(0.0).sqrt(),
+            (1.0 - axis.2 * axis.2).max(0.0).sqrt(),
+        );
+
+        let base = Aabb::new(self.base - disc * self.base_radius, self.base + disc * self.base_radius);
+        let top = Aabb::new(self.top - disc * self.top_radius, self.top + disc * self.top_radius);
+
+        Some(Aabb::surrounding(&base, &top))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::shapes::testing::{close, gray, same, trace};
+
+    fn pointed() -> Cone {
+        Cone::new(Vec3(0.0, 0.0, 0.0), 1.0, Vec3(0.0, 0.0, 2.0), 0.0, gray())
+    }
+
+    #[test]
+    fn hits_the_side() {
+        let cone = pointed();
+        let record = trace(&cone, Vec3(0.0, -3.0, 1.0), Vec3(0.0, 1.0, 0.0), 0.001).unwrap();
+        assert!(close(record.t, 2.5));
+        assert!(same(record.normal, Vec3(0.0, -2.0, 1.0).unit()));
+        assert!(record.font_face);
+        assert!(close(record.u, 0.25));
+        assert!(close(record.v, 0.5));
+    }
+
+    #[test]
+    fn hits_the_caps() {
+        let cone = pointed();
+        let base = trace(&cone, Vec3(0.0, 0.5, -5.0), Vec3(0.0, 0.0, 1.0), 0.001).unwrap();
+        assert!(close(base.t, 5.0));
+        assert!(same(base.normal, Vec3(0.0, 0.0, -1.0)));
+        assert!(close(base.u, 0.75));
+        assert!(close(base.v, 0.0));
+
+        let frustum = Cone::new(Vec3(0.0, 0.0, 0.0), 1.0, Vec3(0.0, 0.0, 2.0), 0.5, gray());
+        let top = trace(&frustum, Vec3(0.0, 0.25, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(top.t, 3.0));
+        assert!(same(top.normal, Vec3(0.0, 0.0, 1.0)));
+        assert!(close(top.v, 1.0));
+    }
+
+    #[test]
+    fn misses_beside_the_narrow_part() {
+        let cone = pointed();
+        assert!(trace(&cone, Vec3(0.8, -3.0, 1.0), Vec3(0.0, 1.0, 0.0), 0.001).is_none());
+        assert!(trace(&cone, Vec3(0.0, -3.0, 2.5), Vec3(0.0, 1.0, 0.0), 0.001).is_none());
+    }
+
+    #[test]
+    fn bounds_the_cone() {
+        let bounds = Cone::new(Vec3(0.0, 0.0, 0.0), 1.0, Vec3(0.0, 0.0, 2.0), 0.5, gray()).bounding_box().unwrap();
+        assert!(same(bounds.minimum, Vec3(-1.0, -1.0, 0.0)));
+        assert!(same(bounds.maximum, Vec3(1.0, 1.0, 2.0)));
+    }
+}
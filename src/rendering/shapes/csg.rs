@@ -0,0 +1,180 @@
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable, Span};
+
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    fn inside(&self, inside_left: bool, inside_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => inside_left || inside_right,
+            CsgOperation::Intersection => inside_left && inside_right,
+            CsgOperation::Difference => inside_left && !inside_right,
+        }
+    }
+}
+
+// Combines two closed shapes. The surfaces of the result keep the materials of the shape they
+// originally belong to.
+pub struct Csg {
+    left: Box<dyn Hittable>,
+    right: Box<dyn Hittable>,
+    operation: CsgOperation,
+}
+
+impl Csg {
+    pub fn new(left: Box<dyn Hittable>, right: Box<dyn Hittable>, operation: CsgOperation) -> Self {
+        Self { left, right, operation }
+    }
+
+    pub fn union(left: Box<dyn Hittable>, right: Box<dyn Hittable>) -> Self {
+        Csg::new(left, right, CsgOperation::Union)
+    }
+
+    pub fn intersection(left: Box<dyn Hittable>, right: Box<dyn Hittable>) -> Self {
+        Csg::new(left, right, CsgOperation::Intersection)
+    }
+
+    pub fn difference(left: Box<dyn Hittable>, right: Box<dyn Hittable>) -> Self {
+        Csg::new(left, right, CsgOperation::Difference)
+    }
+}
+
+struct Event<'a> {
+    record: HitRecord<'a>,
+    left: bool,
+    entering: bool,
+}
+
+impl Hittable for Csg {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        // Boundaries clamped to the queried range are not real surfaces
+        let first = self.spans(ray, t_min, t_max).into_iter()
+            .flat_map(|span| [span.enter, span.exit])
+            .find(|boundary| boundary.material.is_some() && boundary.t > t_min && boundary.t < t_max);
+
+        match first {
+            Some(first) => {
+                *record = first;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let left = self.left.bounding_box()?;
+
+        match self.operation {
+            CsgOperation::Union => Some(Aabb::surrounding(&left, &self.right.bounding_box()?)),
+            CsgOperation::Intersection => match self.right.bounding_box() {
+                Some(right) => Some(Aabb::new(left.minimum.max(&right.minimum), left.maximum.min(&right.maximum))),
+                None => Some(left),
+            },
+            CsgOperation::Difference => Some(left),
+        }
+    }
+
+    fn spans<'a>(&'a self, ray: &Ray, t_min: f32, t_max: f32) -> Vec<Span<'a>> {
+        let mut events = Vec::new();
+        for (spans, left) in [(self.left.spans(ray, t_min, t_max), true), (self.right.spans(ray, t_min, t_max), false)] {
+            for span in spans {
+                events.push(Event { record: span.enter, left, entering: true });
+                events.push(Event { record: span.exit, left, entering: false });
+            }
+        }
+        events.sort_by(|a, b| a.record.t.total_cmp(&b.record.t));
+
+        let mut spans = Vec::new();
+        let mut enter: Option<HitRecord<'a>> = None;
+        let mut inside_left = false;
+        let mut inside_right = false;
+
+        for event in events {
+            let was_inside = self.operation.inside(inside_left, inside_right);
+            if event.left {
+                inside_left = event.entering;
+            } else {
+                inside_right = event.entering;
+            }
+            let is_inside = self.operation.inside(inside_left, inside_right);
+
+            // The stored normal always faces the ray, so only the side of the result changes
+            let mut record = event.record;
+            if !was_inside && is_inside {
+                record.font_face = true;
+                enter = Some(record);
+            } else if was_inside && !is_inside {
+                record.font_face = false;
+                let enter = enter.take().unwrap_or_else(|| HitRecord::boundary(t_min));
+                spans.push(Span { enter, exit: record });
+            }
+        }
+
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::shapes::cuboid::Cuboid;
+    use crate::rendering::shapes::sphere::Sphere;
+    use crate::rendering::shapes::testing::{close, gray, same, trace};
+    use crate::rendering::vector_math::Vec3;
+
+    fn sphere(center: Vec3) -> Box<dyn Hittable> {
+        Box::new(Sphere::new(center, 1.0, gray()))
+    }
+
+    #[test]
+    fn difference_hits_the_far_wall_of_the_removed_box() {
+        // A hole drilled into the sphere from above, ending at its center
+        let hole = Box::new(Cuboid::new(Vec3(-0.25, -0.25, 0.0), Vec3(0.25, 0.25, 2.0), gray()));
+        let csg = Csg::difference(sphere(Vec3(0.0, 0.0, 0.0)), hole);
+
+        let record = trace(&csg, Vec3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(record.t, 5.0));
+        assert!(same(record.normal, Vec3(0.0, 0.0, 1.0)));
+        assert!(record.font_face);
+
+        // Beside the hole the sphere is untouched
+        let record = trace(&csg, Vec3(0.5, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(record.t, 5.0 - 0.75_f32.sqrt()));
+    }
+
+    #[test]
+    fn intersection_returns_only_the_overlap() {
+        let half_space = Box::new(Cuboid::new(Vec3(0.0, -2.0, -2.0), Vec3(2.0, 2.0, 2.0), gray()));
+        let csg = Csg::intersection(sphere(Vec3(0.0, 0.0, 0.0)), half_space);
+
+        let ray = Ray::new(Vec3(-5.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0), 0.0);
+        let spans = csg.spans(&ray, 0.001, f32::INFINITY);
+        assert_eq!(spans.len(), 1);
+        assert!(close(spans[0].enter.t, 5.0));
+        assert!(close(spans[0].exit.t, 6.0));
+        assert!(spans[0].enter.font_face && !spans[0].exit.font_face);
+
+        assert!(trace(&csg, Vec3(-0.5, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).is_none());
+    }
+
+    #[test]
+    fn union_returns_the_outermost_spans() {
+        let csg = Csg::union(sphere(Vec3(-0.5, 0.0, 0.0)), sphere(Vec3(0.5, 0.0, 0.0)));
+
+        let ray = Ray::new(Vec3(-5.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0), 0.0);
+        let spans = csg.spans(&ray, 0.001, f32::INFINITY);
+        assert_eq!(spans.len(), 1);
+        assert!(close(spans[0].enter.t, 3.5));
+        assert!(close(spans[0].exit.t, 6.5));
+        assert!(same(spans[0].exit.normal, Vec3(-1.0, 0.0, 0.0)));
+
+        let bounds = csg.bounding_box().unwrap();
+        assert!(same(bounds.minimum, Vec3(-1.5, -1.0, -1.0)));
+        assert!(same(bounds.maximum, Vec3(1.5, 1.0, 1.0)));
+    }
+}
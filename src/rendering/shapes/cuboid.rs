@@ -0,0 +1,86 @@
+use crate::rendering::materials::material::Material;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+pub struct Cuboid {
+    minimum: Vec3,
+    maximum: Vec3,
+    material: Box<dyn Material>,
+}
+
+impl Cuboid {
+    pub fn new(minimum: Vec3, maximum: Vec3, material: Box<dyn Material>) -> Self {
+        Self { minimum: minimum.min(&maximum), maximum: minimum.max(&maximum), material }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let mut t_near = f32::NEG_INFINITY;
+        let mut t_far = f32::INFINITY;
+        let mut near_axis = 0;
+        let mut far_axis = 0;
+
+        for axis in 0..3 {
+            let inverse = 1.0 / ray.direction().axis(axis);
+            let mut t0 = (self.minimum.axis(axis) - ray.origin().axis(axis)) * inverse;
+            let mut t1 = (self.maximum.axis(axis) - ray.origin().axis(axis)) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            if t0 > t_near {
+                t_near = t0;
+                near_axis = axis;
+            }
+            if t1 < t_far {
+                t_far = t1;
+                far_axis = axis;
+            }
+        }
+
+        if t_near > t_far {
+            return false;
+        }
+
+        let (t, axis) = if t_near >= t_min && t_near <= t_max {
+            (t_near, near_axis)
+        } else if t_far >= t_min && t_far <= t_max {
+            (t_far, far_axis)
+        } else {
+            return false;
+        };
+
+        let position = ray.at(t);
+        let center = (self.minimum + self.maximum) / 2.0;
+        let sign = if position.axis(axis) > center.axis(axis) { 1.0 } else { -1.0 };
+        let outward_normal = match axis {
+            0 => Vec3(sign, 0.0, 0.0),
+            1 => Vec3(0.0, sign, 0.0),
+            _ => Vec3(0.0, 0.0, sign),
+        };
+
+        let relative = position - self.minimum;
+        let size = self.maximum - self.minimum;
+        let (u_axis, v_axis) = match axis {
+            0 => (2, 1),
+            1 => (0, 2),
+            _ => (0, 1),
+        };
+
+        record.t = t;
+        record.position = position;
+        record.set_face_normal(ray, outward_normal);
+        record.u = relative.axis(u_axis) / size.axis(u_axis);
+        record.v = relative.axis(v_axis) / size.axis(v_axis);
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::new(self.minimum, self.maximum))
+    }
+}
@@ -0,0 +1,147 @@
+use std::f32::consts::PI;
+use crate::rendering::materials::material::Material;
+use crate::rendering::onb::Onb;
+use crate::rendering::polynomial::solve_quadratic;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+pub struct Cylinder {
+    base: Vec3,
+    top: Vec3,
+    radius: f32,
+    height: f32,
+    frame: Onb,
+    material: Box<dyn Material>,
+}
+
+impl Cylinder {
+    pub fn new(base: Vec3, top: Vec3, radius: f32, material: Box<dyn Material>) -> Self {
+        let height = (top - base).length();
+        let frame = Onb::new(top - base);
+        Self { base, top, radius, height, frame, material }
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        // Work in a frame where the cylinder axis is +z and the base cap sits at the origin
+        let origin = self.frame.to_local(ray.origin() - self.base);
+        let direction = self.frame.to_local(ray.direction());
+
+        let mut nearest: Option<(f32, Vec3)> = None;
+        let mut consider = |t: f32, normal: Vec3| {
+            if t >= t_min && t <= t_max && nearest.is_none_or(|(closest, _)| t < closest) {
+                nearest = Some((t, normal));
+            }
+        };
+
+        let a = direction.0 * direction.0 + direction.1 * direction.1;
+        let b = 2.0 * (origin.0 * direction.0 + origin.1 * direction.1);
+        let c = origin.0 * origin.0 + origin.1 * origin.1 - self.radius * self.radius;
+        for root in solve_quadratic(a as f64, b as f64, c as f64) {
+            let t = root as f32;
+            let p = origin + direction * t;
+            if p.2 >= 0.0 && p.2 <= self.height {
+                consider(t, Vec3(p.0, p.1, 0.0) / self.radius);
+            }
+        }
+
+        if direction.2 != 0.0 {
+            for (z, normal) in [(0.0, Vec3(0.0, 0.0, -1.0)), (self.height, Vec3(0.0, 0.0, 1.0))] {
+                let t = (z - origin.2) / direction.2;
+                let p = origin + direction * t;
+                if p.0 * p.0 + p.1 * p.1 <= self.radius * self.radius {
+                    consider(t, normal);
+                }
+            }
+        }
+
+        let (t, local_normal) = match nearest {
+            Some(nearest) => nearest,
+            None => return false,
+        };
+
+        let local_position = origin + direction * t;
+
+        record.t = t;
+        record.position = ray.at(t);
+        record.set_face_normal(ray, self.frame.local(local_normal));
+        record.u = (local_position.1.atan2(local_position.0) + PI) / (2.0 * PI);
+        record.v = (local_position.2 / self.height).clamp(0.0, 1.0);
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = self.frame.w;
+        let extent = Vec3(
+            self.radius * (1.0 - axis.0 * axis.0).max(0.0).sqrt(),
+            self.radius * (1.0 - axis.1 * axis.1).max(0.0).sqrt(),
+            self.radius * (1.0 - axis.2 * axis.2).max(0.0).sqrt(),
+        );
+
+        Some(Aabb::new(self.base.min(&self.top) - extent, self.base.max(&self.top) + extent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::shapes::testing::{close, gray, same, trace};
+
+    fn cylinder() -> Cylinder {
+        Cylinder::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, 2.0), 1.0, gray())
+    }
+
+    #[test]
+    fn hits_the_side() {
+        let cylinder = cylinder();
+        let record = trace(&cylinder, Vec3(0.0, -3.0, 1.0), Vec3(0.0, 1.0, 0.0), 0.001).unwrap();
+        assert!(close(record.t, 2.0));
+        assert!(same(record.normal, Vec3(0.0, -1.0, 0.0)));
+        assert!(record.font_face);
+        assert!(close(record.u, 0.25));
+        assert!(close(record.v, 0.5));
+    }
+
+    #[test]
+    fn hits_the_caps() {
+        let cylinder = cylinder();
+        let top = trace(&cylinder, Vec3(0.0, 0.5, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(top.t, 3.0));
+        assert!(same(top.normal, Vec3(0.0, 0.0, 1.0)));
+        assert!(close(top.u, 0.75));
+        assert!(close(top.v, 1.0));
+
+        let bottom = trace(&cylinder, Vec3(0.0, 0.5, -5.0), Vec3(0.0, 0.0, 1.0), 0.001).unwrap();
+        assert!(close(bottom.t, 5.0));
+        assert!(same(bottom.normal, Vec3(0.0, 0.0, -1.0)));
+        assert!(close(bottom.v, 0.0));
+    }
+
+    #[test]
+    fn hits_the_inside_from_within() {
+        let cylinder = cylinder();
+        let record = trace(&cylinder, Vec3(0.0, 0.0, 1.0), Vec3(0.0, 1.0, 0.0), 0.001).unwrap();
+        assert!(close(record.t, 1.0));
+        assert!(same(record.normal, Vec3(0.0, -1.0, 0.0)));
+        assert!(!record.font_face);
+    }
+
+    #[test]
+    fn misses_past_the_top() {
+        let cylinder = cylinder();
+        assert!(trace(&cylinder, Vec3(0.0, -3.0, 2.5), Vec3(0.0, 1.0, 0.0), 0.001).is_none());
+        assert!(trace(&cylinder, Vec3(1.5, -3.0, 1.0), Vec3(0.0, 1.0, 0.0), 0.001).is_none());
+    }
+
+    #[test]
+    fn bounds_the_cylinder() {
+        let bounds = cylinder().bounding_box().unwrap();
+        assert!(same(bounds.minimum, Vec3(-1.0, -1.0, 0.0)));
+        assert!(same(bounds.maximum, Vec3(1.0, 1.0, 2.0)));
+    }
+}
@@ -0,0 +1,97 @@
+use std::f32::consts::PI;
+use rand::Rng;
+use crate::rendering::materials::material::Material;
+use crate::rendering::onb::Onb;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+// Flat circle around the center. The front side is the one the normal points to.
+pub struct Disk {
+    center: Vec3,
+    onb: Onb,
+    radius: f32,
+    material: Box<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Vec3, normal: Vec3, radius: f32, material: Box<dyn Material>) -> Self {
+        Self { center, onb: Onb::new(normal), radius, material }
+    }
+
+    fn random_point(&self) -> Vec3 {
+        let mut rng = random::rng();
+        let radius = self.radius * rng.gen::<f32>().sqrt();
+        let phi = 2.0 * PI * rng.gen::<f32>();
+        self.center + self.onb.local(Vec3(phi.cos() * radius, phi.sin() * radius, 0.0))
+    }
+}
+
+impl Hittable for Disk {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let normal = self.onb.w;
+        let denominator = normal * ray.direction();
+        if denominator.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = (normal * (self.center - ray.origin())) / denominator;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        let position = ray.at(t);
+        let local = self.onb.to_local(position - self.center);
+        let distance_squared = local.0 * local.0 + local.1 * local.1;
+        if distance_squared > self.radius * self.radius {
+            return false;
+        }
+
+        record.t = t;
+        record.position = position;
+        record.set_face_normal(ray, normal);
+        record.u = (local.1.atan2(local.0) + PI) / (2.0 * PI);
+        record.v = distance_squared.sqrt() / self.radius;
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        // Extent of the circle along each axis, thickened so rays along the plane hit the box
+        let normal = self.onb.w;
+        let extent = Vec3(
+            (1.0 - normal.0 * normal.0).max(0.0).sqrt(),
+            (1.0 - normal.1 * normal.1).max(0.0).sqrt(),
+            (1.0 - normal.2 * normal.2).max(0.0).sqrt(),
+        ) * self.radius + 1e-4;
+
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f32 {
+        let mut record = HitRecord::new();
+        if !self.hit(&Ray::new(origin, direction, 0.0), 0.001, f32::INFINITY, &mut record) {
+            return 0.0;
+        }
+
+        let distance_squared = record.t * record.t * direction.length_squared();
+        let cosine = (direction * self.onb.w).abs() / direction.length();
+
+        distance_squared / (cosine * self.area())
+    }
+
+    fn random_direction(&self, origin: Vec3) -> Vec3 {
+        self.random_point() - origin
+    }
+
+    fn sample_surface(&self) -> Option<(Vec3, Vec3)> {
+        Some((self.random_point(), self.onb.w))
+    }
+
+    fn area(&self) -> f32 {
+        PI * self.radius * self.radius
+    }
+}
@@ -1,13 +1,21 @@
-use crate::rendering::materials::lambertian::Lambertian;
+use std::sync::Arc;
 use crate::rendering::materials::material::Material;
 use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
 use crate::rendering::vector_math::Vec3;
 
 pub struct HitRecord<'a> {
     pub position: Vec3,
     pub normal: Vec3,
     pub t: f32,
+    pub u: f32,
+    pub v: f32,
     pub material: Option<&'a dyn Material>,
+    // Index of the scene object that was hit, set by the hierarchy the scene is built from
+    pub object_id: u32,
+    // Distance the ray travelled through a medium to get here, used by media that weight their
+    // hits by it
+    pub distance_inside: f32,
 
     pub font_face: bool,
 }
@@ -22,16 +30,115 @@ impl<'a> HitRecord<'a> {
         }
     }
     pub fn new() -> Self {
-        Self { position: Vec3(0.0,0.0,0.0), normal: Vec3(0.0,0.0,0.0), t: 0.0, material: None, font_face: false }
+        Self { position: Vec3(0.0,0.0,0.0), normal: Vec3(0.0,0.0,0.0), t: 0.0, u: 0.0, v: 0.0, material: None, object_id: 0, distance_inside: 0.0, font_face: false }
+    }
+
+    pub fn boundary(t: f32) -> Self {
+        let mut record = HitRecord::new();
+        record.t = t;
+        record
     }
 }
 
 impl<'a> Clone for HitRecord<'a> {
     fn clone(&self) -> Self {
-        HitRecord {t: self.t, material: self.material, font_face: self.font_face, normal: self.normal, position: self.position}
+        HitRecord {t: self.t, u: self.u, v: self.v, material: self.material, object_id: self.object_id, distance_inside: self.distance_inside, font_face: self.font_face, normal: self.normal, position: self.position}
     }
 }
 
+// The part of a ray between entering and leaving a solid. Boundaries that lie outside of the
+// queried range are clamped to t_min or t_max and carry no material.
+pub struct Span<'a> {
+    pub enter: HitRecord<'a>,
+    pub exit: HitRecord<'a>,
+}
+
+const MAX_SPAN_HITS: usize = 64;
+
 pub trait Hittable {
     fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b;
-}
\ No newline at end of file
+
+    fn bounding_box(&self) -> Option<Aabb>;
+
+    // Walks every surface along the ray and pairs front face hits with the following back face
+    // hits, which is correct for any closed shape
+    fn spans<'a>(&'a self, ray: &Ray, t_min: f32, t_max: f32) -> Vec<Span<'a>> {
+        let mut spans = Vec::new();
+        let mut enter: Option<HitRecord<'a>> = None;
+        let mut t = t_min;
+
+        for _ in 0..MAX_SPAN_HITS {
+            let mut record = HitRecord::new();
+            if !self.hit(ray, t, t_max, &mut record) {
+                break;
+            }
+            t = record.t + 1e-4 * record.t.abs().max(1.0);
+
+            if record.font_face {
+                if enter.is_none() {
+                    enter = Some(record);
+                }
+            } else {
+                let enter = enter.take().unwrap_or_else(|| HitRecord::boundary(t_min));
+                spans.push(Span { enter, exit: record });
+            }
+        }
+
+        if let Some(enter) = enter {
+            spans.push(Span { enter, exit: HitRecord::boundary(t_max) });
+        }
+
+        spans
+    }
+
+    // Solid angle density with which random_direction picks the direction, zero where it misses
+    fn pdf_value(&self, _origin: Vec3, _direction: Vec3) -> f32 {
+        0.0
+    }
+
+    // Direction from the origin towards a random point of the shape, used to sample lights
+    fn random_direction(&self, _origin: Vec3) -> Vec3 {
+        Vec3(1.0, 0.0, 0.0)
+    }
+
+    // Uniformly distributed point on the surface together with its outward normal, used to start
+    // paths on lights
+    fn sample_surface(&self) -> Option<(Vec3, Vec3)> {
+        None
+    }
+
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+// Lets a shape be part of the world and a light source at the same time
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        self.as_ref().hit(ray, t_min, t_max, record)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.as_ref().bounding_box()
+    }
+
+    fn spans<'a>(&'a self, ray: &Ray, t_min: f32, t_max: f32) -> Vec<Span<'a>> {
+        self.as_ref().spans(ray, t_min, t_max)
+    }
+
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f32 {
+        self.as_ref().pdf_value(origin, direction)
+    }
+
+    fn random_direction(&self, origin: Vec3) -> Vec3 {
+        self.as_ref().random_direction(origin)
+    }
+
+    fn sample_surface(&self) -> Option<(Vec3, Vec3)> {
+        self.as_ref().sample_surface()
+    }
+
+    fn area(&self) -> f32 {
+        self.as_ref().area()
+    }
+}
@@ -1,5 +1,6 @@
 use crate::rendering::shapes::hittable::{HitRecord, Hittable};
 use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
 
 pub struct HittableList {
     obejcts: Vec<Box<dyn Hittable>>,
@@ -34,4 +35,18 @@ impl Hittable for HittableList {
 
         hit_anything
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut bounds: Option<Aabb> = None;
+
+        for object in &self.obejcts {
+            let object_bounds = object.bounding_box()?;
+            bounds = Some(match bounds {
+                Some(bounds) => Aabb::surrounding(&bounds, &object_bounds),
+                None => object_bounds,
+            });
+        }
+
+        bounds
+    }
 }
\ No newline at end of file
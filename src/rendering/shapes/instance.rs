@@ -0,0 +1,96 @@
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::transform::{Pose, Transform};
+
+fn hit_transformed<'a, 'b>(object: &'a dyn Hittable, transform: &Transform, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+    // The direction is not normalized, so t is the same in object and world space
+    let local_ray = Ray::new(transform.inverse_point(ray.origin()), transform.inverse_vector(ray.direction()), ray.time());
+
+    if !object.hit(&local_ray, t_min, t_max, record) {
+        return false;
+    }
+
+    record.position = transform.point(record.position);
+    record.normal = transform.normal(record.normal);
+
+    true
+}
+
+// Places an object in the scene with a transformation, so the same shape can be reused
+pub struct Instance {
+    object: Box<dyn Hittable>,
+    transform: Transform,
+}
+
+impl Instance {
+    pub fn new(object: Box<dyn Hittable>, transform: Transform) -> Self {
+        Self { object, transform }
+    }
+}
+
+impl Hittable for Instance {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        hit_transformed(self.object.as_ref(), &self.transform, ray, t_min, t_max, record)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.transform.bounding_box(&self.object.bounding_box()?))
+    }
+}
+
+const BOUNDS_STEPS: u32 = 32;
+
+// Instance moving between two poses over time0 to time1, holding still outside of that interval
+pub struct AnimatedInstance {
+    object: Box<dyn Hittable>,
+    start: Pose,
+    end: Pose,
+    time0: f32,
+    time1: f32,
+}
+
+impl AnimatedInstance {
+    pub fn new(object: Box<dyn Hittable>, start: Pose, end: Pose, time0: f32, time1: f32) -> Self {
+        Self { object, start, end, time0, time1 }
+    }
+
+    pub fn transform(&self, time: f32) -> Transform {
+        let t = if self.time1 == self.time0 {
+            0.0
+        } else {
+            ((time - self.time0) / (self.time1 - self.time0)).clamp(0.0, 1.0)
+        };
+
+        self.start.interpolate(&self.end, t).transform()
+    }
+}
+
+impl Hittable for AnimatedInstance {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        hit_transformed(self.object.as_ref(), &self.transform(ray.time()), ray, t_min, t_max, record)
+    }
+
+    // Rotations move along arcs, so the bounds of many poses along the way are combined and padded
+    // by the largest gap between two consecutive ones
+    fn bounding_box(&self) -> Option<Aabb> {
+        let object_bounds = self.object.bounding_box()?;
+
+        let mut bounds = self.transform(self.time0).bounding_box(&object_bounds);
+        let mut previous = bounds;
+        let mut padding = 0.0f32;
+
+        for step in 1..=BOUNDS_STEPS {
+            let time = self.time0 + (self.time1 - self.time0) * step as f32 / BOUNDS_STEPS as f32;
+            let current = self.transform(time).bounding_box(&object_bounds);
+
+            padding = padding
+                .max((current.minimum - previous.minimum).length())
+                .max((current.maximum - previous.maximum).length());
+            bounds = Aabb::surrounding(&bounds, &current);
+            previous = current;
+        }
+
+        Some(Aabb::new(bounds.minimum + -padding, bounds.maximum + padding))
+    }
+}
@@ -1,3 +1,19 @@
 pub mod sphere;
 pub mod hittable_list;
 pub mod hittable;
+pub mod aabb;
+pub mod cylinder;
+pub mod cone;
+pub mod torus;
+pub mod capsule;
+pub mod cuboid;
+pub mod csg;
+pub mod sdf_object;
+pub mod bvh;
+pub mod moving_sphere;
+pub mod instance;
+pub mod quad;
+pub mod triangle;
+pub mod disk;
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,73 @@
+use crate::rendering::materials::material::Material;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::shapes::sphere::Sphere;
+use crate::rendering::vector_math::Vec3;
+
+// Sphere moving linearly from center0 at time0 to center1 at time1. It stays put outside of that
+// interval, so the bounds cover every time a ray can have.
+pub struct MovingSphere {
+    center0: Vec3,
+    center1: Vec3,
+    time0: f32,
+    time1: f32,
+    radius: f32,
+    material: Box<dyn Material>,
+}
+
+impl MovingSphere {
+    pub fn new(center0: Vec3, center1: Vec3, time0: f32, time1: f32, radius: f32, material: Box<dyn Material>) -> Self {
+        Self { center0, center1, time0, time1, radius, material }
+    }
+
+    pub fn center(&self, time: f32) -> Vec3 {
+        if self.time1 == self.time0 {
+            return self.center0;
+        }
+        let t = ((time - self.time0) / (self.time1 - self.time0)).clamp(0.0, 1.0);
+        self.center0 + (self.center1 - self.center0) * t
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let center = self.center(ray.time());
+
+        let oc = ray.origin() - center;
+        let a = ray.direction().length_squared();
+        let half_b = oc * ray.direction();
+        let c = oc.length_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return false;
+        }
+
+        let sqrt = discriminant.sqrt();
+        let mut root = (-half_b - sqrt) / a;
+
+        if root < t_min || root > t_max {
+            root = (-half_b + sqrt) / a;
+            if root < t_min || root > t_max {
+                return false;
+            }
+        }
+
+        record.t = root;
+        record.position = ray.at(root);
+        let outward_normal = (record.position - center) / self.radius;
+        record.set_face_normal(ray, outward_normal);
+        (record.u, record.v) = Sphere::uv(outward_normal);
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3(self.radius, self.radius, self.radius);
+        let start = Aabb::new(self.center0 - extent, self.center0 + extent);
+        let end = Aabb::new(self.center1 - extent, self.center1 + extent);
+
+        Some(Aabb::surrounding(&start, &end))
+    }
+}
@@ -0,0 +1,100 @@
+use rand::Rng;
+use crate::rendering::materials::material::Material;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+// Parallelogram spanned by the edges u and v starting at the corner q. The front side is the one
+// the normal u x v points to.
+pub struct Quad {
+    q: Vec3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    normal: Vec3,
+    d: f32,
+    area: f32,
+    material: Box<dyn Material>,
+}
+
+impl Quad {
+    pub fn new(q: Vec3, u: Vec3, v: Vec3, material: Box<dyn Material>) -> Self {
+        let n = u.cross(&v);
+        let normal = n.unit();
+        let d = normal * q;
+        let w = n / (n * n);
+        let area = n.length();
+
+        Self { q, u, v, w, normal, d, area, material }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let denominator = self.normal * ray.direction();
+        if denominator.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = (self.d - self.normal * ray.origin()) / denominator;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        // Express the hit in the coordinates of the edges to check if it lies inside
+        let position = ray.at(t);
+        let planar = position - self.q;
+        let alpha = self.w * planar.cross(&self.v);
+        let beta = self.w * self.u.cross(&planar);
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return false;
+        }
+
+        record.t = t;
+        record.position = position;
+        record.set_face_normal(ray, self.normal);
+        record.u = alpha;
+        record.v = beta;
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let corners = [self.q, self.q + self.u, self.q + self.v, self.q + self.u + self.v];
+        let minimum = corners.iter().fold(corners[0], |minimum, corner| minimum.min(corner));
+        let maximum = corners.iter().fold(corners[0], |maximum, corner| maximum.max(corner));
+
+        // Give flat quads some thickness, otherwise rays along the plane miss the box
+        Some(Aabb::new(minimum + -1e-4, maximum + 1e-4))
+    }
+
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f32 {
+        let mut record = HitRecord::new();
+        if !self.hit(&Ray::new(origin, direction, 0.0), 0.001, f32::INFINITY, &mut record) {
+            return 0.0;
+        }
+
+        let distance_squared = record.t * record.t * direction.length_squared();
+        let cosine = (direction * self.normal).abs() / direction.length();
+
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random_direction(&self, origin: Vec3) -> Vec3 {
+        let mut rng = random::rng();
+        let point = self.q + self.u * rng.gen::<f32>() + self.v * rng.gen::<f32>();
+        point - origin
+    }
+
+    fn sample_surface(&self) -> Option<(Vec3, Vec3)> {
+        let mut rng = random::rng();
+        Some((self.q + self.u * rng.gen::<f32>() + self.v * rng.gen::<f32>(), self.normal))
+    }
+
+    fn area(&self) -> f32 {
+        self.area
+    }
+}
@@ -0,0 +1,88 @@
+use crate::rendering::materials::material::Material;
+use crate::rendering::ray::Ray;
+use crate::rendering::sdf::distance_function::DistanceFunction;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::shapes::sphere::Sphere;
+use crate::rendering::vector_math::Vec3;
+
+const MAX_STEPS: u32 = 256;
+const EPSILON: f32 = 1e-4;
+
+// Renders a distance function by sphere tracing. The bounds limit how far rays are marched, so
+// they have to enclose the whole surface.
+pub struct SdfObject {
+    function: Box<dyn DistanceFunction>,
+    bounds: Aabb,
+    material: Box<dyn Material>,
+    step_scale: f32,
+}
+
+impl SdfObject {
+    pub fn new(function: Box<dyn DistanceFunction>, bounds: Aabb, material: Box<dyn Material>) -> Self {
+        Self { function, bounds, material, step_scale: 1.0 }
+    }
+
+    // Distance functions that overestimate (twists, some fractals) need steps smaller than one
+    pub fn set_step_scale(&mut self, step_scale: f32) {
+        self.step_scale = step_scale;
+    }
+
+    fn normal(&self, p: Vec3) -> Vec3 {
+        // Tetrahedral central differences need four evaluations instead of six
+        let h = EPSILON;
+        let k0 = Vec3(1.0, -1.0, -1.0);
+        let k1 = Vec3(-1.0, -1.0, 1.0);
+        let k2 = Vec3(-1.0, 1.0, -1.0);
+        let k3 = Vec3(1.0, 1.0, 1.0);
+
+        (k0 * self.function.distance(p + k0 * h)
+            + k1 * self.function.distance(p + k1 * h)
+            + k2 * self.function.distance(p + k2 * h)
+            + k3 * self.function.distance(p + k3 * h)).unit()
+    }
+}
+
+impl Hittable for SdfObject {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let (start, end) = match self.bounds.intersect(ray, t_min, t_max) {
+            Some(range) => range,
+            None => return false,
+        };
+
+        let length = ray.direction().length();
+        let mut t = start;
+
+        // Rays leaving the surface start right on it, so step off before looking for a hit
+        let mut leaving = self.function.distance(ray.at(t)).abs() < EPSILON;
+
+        for _ in 0..MAX_STEPS {
+            let distance = self.function.distance(ray.at(t)).abs();
+
+            if leaving {
+                leaving = distance < EPSILON;
+            } else if distance < EPSILON {
+                let outward_normal = self.normal(ray.at(t));
+
+                record.t = t;
+                record.position = ray.at(t);
+                record.set_face_normal(ray, outward_normal);
+                (record.u, record.v) = Sphere::uv(outward_normal);
+                record.material = Some(self.material.as_ref());
+
+                return true;
+            }
+
+            t += distance.max(EPSILON) * self.step_scale / length;
+            if t > end {
+                break;
+            }
+        }
+
+        false
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
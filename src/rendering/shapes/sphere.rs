@@ -1,4 +1,9 @@
+use std::f32::consts::PI;
+use rand::Rng;
 use crate::rendering::materials::material::Material;
+use crate::rendering::onb::Onb;
+use crate::rendering::random;
+use crate::rendering::shapes::aabb::Aabb;
 use crate::rendering::shapes::hittable::{HitRecord, Hittable};
 use crate::rendering::ray::Ray;
 use crate::rendering::vector_math::Vec3;
@@ -13,6 +18,13 @@ impl Sphere {
     pub fn new(center: Vec3, radius: f32, material: Box<dyn Material>) -> Self {
         Self { center, radius, material }
     }
+
+    pub fn uv(outward_normal: Vec3) -> (f32, f32) {
+        let theta = (-outward_normal.1).acos();
+        let phi = (-outward_normal.2).atan2(outward_normal.0) + PI;
+
+        (phi / (2.0 * PI), theta / PI)
+    }
 }
 
 impl Hittable for Sphere {
@@ -40,8 +52,58 @@ impl Hittable for Sphere {
         record.position = ray.at(root);
         let outward_normal= (record.position - self.center) / self.radius;
         record.set_face_normal(ray, outward_normal);
+        (record.u, record.v) = Sphere::uv(outward_normal);
         record.material = Some(self.material.as_ref());
 
         true
     }
-}
\ No newline at end of file
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3(self.radius, self.radius, self.radius);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f32 {
+        let mut record = HitRecord::new();
+        if !self.hit(&Ray::new(origin, direction, 0.0), 0.001, f32::INFINITY, &mut record) {
+            return 0.0;
+        }
+
+        // From inside every direction is picked equally, from outside only the visible cone
+        let distance_squared = (self.center - origin).length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return 1.0 / (4.0 * PI);
+        }
+
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    fn random_direction(&self, origin: Vec3) -> Vec3 {
+        let direction = self.center - origin;
+        let distance_squared = direction.length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return Vec3::random_unit_vector();
+        }
+
+        let mut rng = random::rng();
+        let r1: f32 = rng.gen();
+        let r2: f32 = rng.gen();
+
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * r1;
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+
+        Onb::new(direction).local(Vec3(phi.cos() * sin_theta, phi.sin() * sin_theta, z))
+    }
+
+    fn sample_surface(&self) -> Option<(Vec3, Vec3)> {
+        let normal = Vec3::random_unit_vector();
+        Some((self.center + normal * self.radius, normal))
+    }
+
+    fn area(&self) -> f32 {
+        4.0 * PI * self.radius * self.radius
+    }
+}
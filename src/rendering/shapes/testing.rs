@@ -0,0 +1,27 @@
+use crate::rendering::materials::lambertian::Lambertian;
+use crate::rendering::materials::material::Material;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+pub fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+pub fn same(a: Vec3, b: Vec3) -> bool {
+    close(a.0, b.0) && close(a.1, b.1) && close(a.2, b.2)
+}
+
+pub fn gray() -> Box<dyn Material> {
+    Box::new(Lambertian::new(Vec3(0.5, 0.5, 0.5)))
+}
+
+// First hit along the ray after t_min, if there is one
+pub fn trace<'a>(shape: &'a dyn Hittable, origin: Vec3, direction: Vec3, t_min: f32) -> Option<HitRecord<'a>> {
+    let mut record = HitRecord::new();
+    if shape.hit(&Ray::new(origin, direction, 0.0), t_min, f32::INFINITY, &mut record) {
+        Some(record)
+    } else {
+        None
+    }
+}
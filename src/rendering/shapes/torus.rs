@@ -0,0 +1,161 @@
+use std::f32::consts::PI;
+use crate::rendering::materials::material::Material;
+use crate::rendering::onb::Onb;
+use crate::rendering::polynomial::solve_quartic;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+pub struct Torus {
+    center: Vec3,
+    major_radius: f32,
+    minor_radius: f32,
+    frame: Onb,
+    material: Box<dyn Material>,
+}
+
+impl Torus {
+    // The ring of radius major_radius lies in the plane perpendicular to axis
+    pub fn new(center: Vec3, axis: Vec3, major_radius: f32, minor_radius: f32, material: Box<dyn Material>) -> Self {
+        Self { center, major_radius, minor_radius, frame: Onb::new(axis), material }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        if !self.bounding_box().unwrap().hit(ray, t_min, t_max) {
+            return false;
+        }
+
+        // Solve in a frame where the axis is +z, with a unit direction to keep the quartic well conditioned
+        let length = ray.direction().length();
+        let o = self.frame.to_local(ray.origin() - self.center);
+        let d = self.frame.to_local(ray.direction()) / length;
+
+        let (ox, oy, oz) = (o.0 as f64, o.1 as f64, o.2 as f64);
+        let (dx, dy, dz) = (d.0 as f64, d.1 as f64, d.2 as f64);
+        let major = self.major_radius as f64 * self.major_radius as f64;
+        let minor = self.minor_radius as f64 * self.minor_radius as f64;
+
+        let m = ox * ox + oy * oy + oz * oz;
+        let n = ox * dx + oy * dy + oz * dz;
+        let k = m + major - minor;
+
+        let roots = solve_quartic(
+            1.0,
+            4.0 * n,
+            4.0 * n * n + 2.0 * k - 4.0 * major * (dx * dx + dy * dy),
+            4.0 * n * k - 8.0 * major * (ox * dx + oy * dy),
+            k * k - 4.0 * major * (ox * ox + oy * oy),
+        );
+
+        let t = roots.into_iter()
+            .map(|root| root as f32 / length)
+            .filter(|t| *t >= t_min && *t <= t_max)
+            .fold(None, |nearest: Option<f32>, t| Some(nearest.map_or(t, |nearest| nearest.min(t))));
+
+        let t = match t {
+            Some(t) => t,
+            None => return false,
+        };
+
+        // The normal points away from the closest point on the ring
+        let p = o + d * (t * length);
+        let ring = Vec3(p.0, p.1, 0.0).unit() * self.major_radius;
+        let local_normal = (p - ring).unit();
+
+        record.t = t;
+        record.position = ray.at(t);
+        record.set_face_normal(ray, self.frame.local(local_normal));
+        record.u = (p.1.atan2(p.0) + PI) / (2.0 * PI);
+        record.v = (p.2.atan2(Vec3(p.0, p.1, 0.0).length() - self.major_radius) + PI) / (2.0 * PI);
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let axis = self.frame.w;
+        let extent = Vec3(
+            self.major_radius * (1.0 - axis.0 * axis.0).max(0.0).sqrt() + self.minor_radius,
+            self.major_radius * (1.0 - axis.1 * axis.1).max(0.0).sqrt() + self.minor_radius,
+            self.major_radius * (1.0 - axis.2 * axis.2).max(0.0).sqrt() + self.minor_radius,
+        );
+
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::shapes::testing::{close, gray, same, trace};
+
+    fn torus() -> Torus {
+        Torus::new(Vec3(0.0, 0.0, 0.0), Vec3(0.0, 0.0, 1.0), 2.0, 0.5, gray())
+    }
+
+    #[test]
+    fn hits_all_four_roots() {
+        let torus = torus();
+        let origin = Vec3(-5.0, 0.0, 0.0);
+        let direction = Vec3(1.0, 0.0, 0.0);
+
+        // Entering and leaving the near side of the tube, then the far side
+        let expected = [(2.5, true), (3.5, false), (6.5, true), (7.5, false)];
+        let mut t_min = 0.001;
+        for (t, front_face) in expected {
+            let record = trace(&torus, origin, direction, t_min).unwrap();
+            assert!(close(record.t, t));
+            assert!(record.font_face == front_face);
+            assert!(same(record.normal, Vec3(-1.0, 0.0, 0.0)));
+            t_min = t + 0.1;
+        }
+        assert!(trace(&torus, origin, direction, t_min).is_none());
+    }
+
+    #[test]
+    fn scales_t_with_the_direction() {
+        let torus = torus();
+        let record = trace(&torus, Vec3(-5.0, 0.0, 0.0), Vec3(2.0, 0.0, 0.0), 0.001).unwrap();
+        assert!(close(record.t, 1.25));
+        assert!(close(record.u, 0.5));
+        assert!(close(record.v, 0.5));
+    }
+
+    #[test]
+    fn hits_the_top_of_the_tube() {
+        let torus = torus();
+        let record = trace(&torus, Vec3(0.0, 2.0, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(record.t, 4.5));
+        assert!(same(record.normal, Vec3(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn measures_v_around_the_tube() {
+        // 60 degrees up from the outer equator of the tube
+        let torus = torus();
+        let angle = PI / 3.0;
+        let radial = 2.0 + 0.5 * angle.cos();
+        let height = 0.5 * angle.sin();
+        let record = trace(&torus, Vec3(0.0, radial, 5.0), Vec3(0.0, 0.0, -1.0), 0.001).unwrap();
+        assert!(close(record.t, 5.0 - height));
+        assert!(same(record.normal, Vec3(0.0, angle.cos(), angle.sin())));
+        assert!(close(record.v, (angle + PI) / (2.0 * PI)));
+    }
+
+    #[test]
+    fn misses_through_the_hole() {
+        let torus = torus();
+        assert!(trace(&torus, Vec3(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0), 0.001).is_none());
+        assert!(trace(&torus, Vec3(-5.0, 0.0, 1.0), Vec3(1.0, 0.0, 0.0), 0.001).is_none());
+    }
+
+    #[test]
+    fn bounds_the_torus() {
+        let bounds = torus().bounding_box().unwrap();
+        assert!(same(bounds.minimum, Vec3(-2.5, -2.5, -0.5)));
+        assert!(same(bounds.maximum, Vec3(2.5, 2.5, 0.5)));
+    }
+}
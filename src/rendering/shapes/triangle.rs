@@ -0,0 +1,104 @@
+use rand::Rng;
+use crate::rendering::materials::material::Material;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+// The front side is the one from which the vertices appear counterclockwise
+pub struct Triangle {
+    v0: Vec3,
+    v1: Vec3,
+    v2: Vec3,
+    normal: Vec3,
+    area: f32,
+    material: Box<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(v0: Vec3, v1: Vec3, v2: Vec3, material: Box<dyn Material>) -> Self {
+        let n = (v1 - v0).cross(&(v2 - v0));
+        Self { v0, v1, v2, normal: n.unit(), area: n.length() / 2.0, material }
+    }
+
+    fn random_point(&self) -> Vec3 {
+        let mut rng = random::rng();
+        let r1 = rng.gen::<f32>().sqrt();
+        let r2: f32 = rng.gen();
+
+        self.v0 * (1.0 - r1) + self.v1 * (r1 * (1.0 - r2)) + self.v2 * (r1 * r2)
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        // Möller-Trumbore
+        let edge1 = self.v1 - self.v0;
+        let edge2 = self.v2 - self.v0;
+        let p = ray.direction().cross(&edge2);
+        let determinant = edge1 * p;
+        if determinant.abs() < 1e-8 {
+            return false;
+        }
+
+        let inverse = 1.0 / determinant;
+        let s = ray.origin() - self.v0;
+        let u = (s * p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return false;
+        }
+
+        let q = s.cross(&edge1);
+        let v = (ray.direction() * q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return false;
+        }
+
+        let t = (edge2 * q) * inverse;
+        if t < t_min || t > t_max {
+            return false;
+        }
+
+        record.t = t;
+        record.position = ray.at(t);
+        record.set_face_normal(ray, self.normal);
+        record.u = u;
+        record.v = v;
+        record.material = Some(self.material.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let minimum = self.v0.min(&self.v1).min(&self.v2);
+        let maximum = self.v0.max(&self.v1).max(&self.v2);
+
+        // Give axis aligned triangles some thickness, otherwise rays along the plane miss the box
+        Some(Aabb::new(minimum + -1e-4, maximum + 1e-4))
+    }
+
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f32 {
+        let mut record = HitRecord::new();
+        if !self.hit(&Ray::new(origin, direction, 0.0), 0.001, f32::INFINITY, &mut record) {
+            return 0.0;
+        }
+
+        let distance_squared = record.t * record.t * direction.length_squared();
+        let cosine = (direction * self.normal).abs() / direction.length();
+
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random_direction(&self, origin: Vec3) -> Vec3 {
+        self.random_point() - origin
+    }
+
+    fn sample_surface(&self) -> Option<(Vec3, Vec3)> {
+        Some((self.random_point(), self.normal))
+    }
+
+    fn area(&self) -> f32 {
+        self.area
+    }
+}
@@ -0,0 +1,27 @@
+use crate::rendering::textures::texture::Texture;
+use crate::rendering::vector_math::Vec3;
+
+// Alternates between two textures in cubes of the given size in space, so it doesn't depend on
+// the uv mapping of the surface
+pub struct CheckerTexture {
+    even: Box<dyn Texture>,
+    odd: Box<dyn Texture>,
+    size: f32,
+}
+
+impl CheckerTexture {
+    pub fn new(even: Box<dyn Texture>, odd: Box<dyn Texture>, size: f32) -> Self {
+        Self { even, odd, size }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f32, v: f32, position: Vec3) -> Vec3 {
+        let cell = (position.0 / self.size).floor() + (position.1 / self.size).floor() + (position.2 / self.size).floor();
+        if cell as i64 % 2 == 0 {
+            self.even.value(u, v, position)
+        } else {
+            self.odd.value(u, v, position)
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+use crate::rendering::textures::texture::Texture;
+use crate::rendering::vector_math::Vec3;
+
+// Image stretched over the uv range of a surface, with v = 0 at the bottom row
+pub struct ImageTexture {
+    width: u32,
+    height: u32,
+    pixels: Vec<Vec3>,
+}
+
+impl ImageTexture {
+    // Colors are usually stored in sRGB, while data like roughness maps is already linear
+    pub fn load(path: &Path, srgb: bool) -> Result<Self, String> {
+        let image = image::open(path).map_err(|e| e.to_string())?.to_rgb32f();
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return Err(format!("image {} is empty", path.display()));
+        }
+
+        let pixels = image.pixels()
+            .map(|pixel| {
+                let color = Vec3(pixel[0], pixel[1], pixel[2]);
+                if srgb { Vec3(color.0.powf(2.2), color.1.powf(2.2), color.2.powf(2.2)) } else { color }
+            })
+            .collect();
+
+        Ok(Self { width, height, pixels })
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _position: Vec3) -> Vec3 {
+        let x = (u.clamp(0.0, 1.0) * self.width as f32) as u32;
+        let y = ((1.0 - v.clamp(0.0, 1.0)) * self.height as f32) as u32;
+        let x = x.min(self.width - 1);
+        let y = y.min(self.height - 1);
+
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
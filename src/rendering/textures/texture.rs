@@ -0,0 +1,27 @@
+use crate::rendering::vector_math::Vec3;
+
+// Color or parameter varying over a surface, looked up by the uv coordinates of a hit. Scalar
+// parameters use the first channel.
+pub trait Texture {
+    fn value(&self, u: f32, v: f32, position: Vec3) -> Vec3;
+}
+
+pub struct SolidColor {
+    color: Vec3,
+}
+
+impl SolidColor {
+    pub fn new(color: Vec3) -> Self {
+        Self { color }
+    }
+
+    pub fn scalar(value: f32) -> Self {
+        Self { color: Vec3(value, value, value) }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _position: Vec3) -> Vec3 {
+        self.color
+    }
+}
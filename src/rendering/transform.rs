@@ -0,0 +1,218 @@
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::vector_math::Vec3;
+
+type Matrix = [[f32; 4]; 3];
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+];
+
+// Multiplies two affine matrices, the implicit last row of both is (0, 0, 0, 1)
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 3];
+    for (row, result_row) in result.iter_mut().enumerate() {
+        for (column, value) in result_row.iter_mut().enumerate() {
+            *value = a[row][0] * b[0][column] + a[row][1] * b[1][column] + a[row][2] * b[2][column];
+        }
+        result_row[3] += a[row][3];
+    }
+    result
+}
+
+// Affine transformation which keeps its inverse around for transforming rays into object space
+pub struct Transform {
+    matrix: Matrix,
+    inverse: Matrix,
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self { matrix: IDENTITY, inverse: IDENTITY }
+    }
+
+    pub fn translation(offset: Vec3) -> Self {
+        let mut matrix = IDENTITY;
+        let mut inverse = IDENTITY;
+        for axis in 0..3 {
+            matrix[axis][3] = offset.axis(axis);
+            inverse[axis][3] = -offset.axis(axis);
+        }
+        Self { matrix, inverse }
+    }
+
+    pub fn scaling(scale: Vec3) -> Self {
+        let mut matrix = IDENTITY;
+        let mut inverse = IDENTITY;
+        for axis in 0..3 {
+            matrix[axis][axis] = scale.axis(axis);
+            inverse[axis][axis] = 1.0 / scale.axis(axis);
+        }
+        Self { matrix, inverse }
+    }
+
+    // Rotates counterclockwise around the axis by the angle in radians
+    pub fn rotation(axis: Vec3, angle: f32) -> Self {
+        Transform::from_quaternion(&Quaternion::from_axis_angle(axis, angle))
+    }
+
+    pub fn from_quaternion(rotation: &Quaternion) -> Self {
+        let Quaternion(w, Vec3(x, y, z)) = *rotation;
+
+        let matrix = [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y), 0.0],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x), 0.0],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y), 0.0],
+        ];
+
+        // Rotations are orthogonal, so the inverse is the transpose
+        let mut inverse = IDENTITY;
+        for (row, inverse_row) in inverse.iter_mut().enumerate() {
+            for (column, value) in inverse_row.iter_mut().take(3).enumerate() {
+                *value = matrix[column][row];
+            }
+        }
+
+        Self { matrix, inverse }
+    }
+
+    // Applies this transformation first and the next one afterwards
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            matrix: multiply(&next.matrix, &self.matrix),
+            inverse: multiply(&self.inverse, &next.inverse),
+        }
+    }
+
+    fn apply(matrix: &Matrix, v: Vec3, w: f32) -> Vec3 {
+        Vec3(
+            matrix[0][0] * v.0 + matrix[0][1] * v.1 + matrix[0][2] * v.2 + matrix[0][3] * w,
+            matrix[1][0] * v.0 + matrix[1][1] * v.1 + matrix[1][2] * v.2 + matrix[1][3] * w,
+            matrix[2][0] * v.0 + matrix[2][1] * v.1 + matrix[2][2] * v.2 + matrix[2][3] * w,
+        )
+    }
+
+    pub fn point(&self, p: Vec3) -> Vec3 {
+        Transform::apply(&self.matrix, p, 1.0)
+    }
+
+    pub fn vector(&self, v: Vec3) -> Vec3 {
+        Transform::apply(&self.matrix, v, 0.0)
+    }
+
+    pub fn inverse_point(&self, p: Vec3) -> Vec3 {
+        Transform::apply(&self.inverse, p, 1.0)
+    }
+
+    pub fn inverse_vector(&self, v: Vec3) -> Vec3 {
+        Transform::apply(&self.inverse, v, 0.0)
+    }
+
+    // Normals have to be transformed by the inverse transpose to stay perpendicular to the surface
+    pub fn normal(&self, n: Vec3) -> Vec3 {
+        let m = &self.inverse;
+        Vec3(
+            m[0][0] * n.0 + m[1][0] * n.1 + m[2][0] * n.2,
+            m[0][1] * n.0 + m[1][1] * n.1 + m[2][1] * n.2,
+            m[0][2] * n.0 + m[1][2] * n.1 + m[2][2] * n.2,
+        ).unit()
+    }
+
+    pub fn bounding_box(&self, bounds: &Aabb) -> Aabb {
+        let mut minimum = Vec3(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut maximum = Vec3(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+
+        for corner in 0..8 {
+            let p = Vec3(
+                if corner & 1 == 0 { bounds.minimum.0 } else { bounds.maximum.0 },
+                if corner & 2 == 0 { bounds.minimum.1 } else { bounds.maximum.1 },
+                if corner & 4 == 0 { bounds.minimum.2 } else { bounds.maximum.2 },
+            );
+            let transformed = self.point(p);
+            minimum = minimum.min(&transformed);
+            maximum = maximum.max(&transformed);
+        }
+
+        Aabb::new(minimum, maximum)
+    }
+}
+
+// Unit quaternion (w, xyz) describing a rotation
+pub struct Quaternion(pub f32, pub Vec3);
+
+impl Quaternion {
+    pub fn identity() -> Self {
+        Quaternion(1.0, Vec3(0.0, 0.0, 0.0))
+    }
+
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let half = angle / 2.0;
+        Quaternion(half.cos(), axis.unit() * half.sin())
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.0 * other.0 + self.1 * other.1
+    }
+
+    // Spherical interpolation, so the rotation speed stays constant over the interval
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Quaternion {
+        let mut cos = self.dot(other);
+        let mut other = *other;
+
+        // Take the short way around
+        if cos < 0.0 {
+            cos = -cos;
+            other = Quaternion(-other.0, -1.0 * other.1);
+        }
+
+        let (a, b) = if cos > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos.acos();
+            let sin = theta.sin();
+            (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+        };
+
+        let w = self.0 * a + other.0 * b;
+        let xyz = self.1 * a + other.1 * b;
+        let length = (w * w + xyz.length_squared()).sqrt();
+
+        Quaternion(w / length, xyz / length)
+    }
+}
+
+impl Clone for Quaternion {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for Quaternion {}
+
+// Scale, then rotate, then translate. Unlike matrices, poses can be interpolated for animations.
+pub struct Pose {
+    pub translation: Vec3,
+    pub rotation: Quaternion,
+    pub scale: Vec3,
+}
+
+impl Pose {
+    pub fn new(translation: Vec3, rotation: Quaternion, scale: Vec3) -> Self {
+        Self { translation, rotation, scale }
+    }
+
+    pub fn interpolate(&self, other: &Pose, t: f32) -> Pose {
+        Pose {
+            translation: self.translation + (other.translation - self.translation) * t,
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.scale + (other.scale - self.scale) * t,
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        Transform::scaling(self.scale)
+            .then(&Transform::from_quaternion(&self.rotation))
+            .then(&Transform::translation(self.translation))
+    }
+}
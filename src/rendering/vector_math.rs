@@ -1,5 +1,6 @@
 use std::ops::{Add, Div, Mul, Sub};
 use rand::Rng;
+use crate::rendering::random;
 
 pub struct Vec3(pub f32, pub f32, pub f32);
 
@@ -17,7 +18,7 @@ impl Vec3 {
     }
 
     pub fn random_vector() -> Self {
-        let mut rng = rand::thread_rng();
+        let mut rng = random::rng();
 
         Vec3(rng.gen(), rng.gen(), rng.gen())
     }
@@ -27,7 +28,21 @@ impl Vec3 {
     }
 
     pub fn random_unit_vector() -> Self {
-        Vec3::random_vector().unit()
+        Vec3::random_in_unit_sphere().unit()
+    }
+
+    // Uniformly distributed direction, from two numbers between 0 and 1
+    pub fn sample_unit_vector(sample: (f32, f32)) -> Vec3 {
+        let z = 1.0 - 2.0 * sample.0;
+        let radius = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * std::f32::consts::PI * sample.1;
+
+        Vec3(phi.cos() * radius, phi.sin() * radius, z)
+    }
+
+    // Uniformly distributed point in the unit sphere, the third number picks the distance
+    pub fn sample_in_unit_sphere(sample: (f32, f32), distance: f32) -> Vec3 {
+        Vec3::sample_unit_vector(sample) * distance.cbrt()
     }
 
     pub fn random_in_unit_sphere() -> Vec3 {
@@ -40,6 +55,19 @@ impl Vec3 {
         }
     }
 
+    // Direction around +z with a density proportional to the cosine to +z
+    pub fn random_cosine_direction() -> Vec3 {
+        let mut rng = random::rng();
+        Vec3::sample_cosine_direction((rng.gen(), rng.gen()))
+    }
+
+    pub fn sample_cosine_direction(sample: (f32, f32)) -> Vec3 {
+        let phi = 2.0 * std::f32::consts::PI * sample.0;
+        let radius = sample.1.sqrt();
+
+        Vec3(phi.cos() * radius, phi.sin() * radius, (1.0 - sample.1).sqrt())
+    }
+
     pub fn near_zero(&self) -> bool {
         let s = 1e-8;
         return self.0.abs() < s && self.1.abs() < s && self.2.abs() < s;
@@ -52,6 +80,30 @@ impl Vec3 {
     pub fn mul(&self, other: &Vec3) -> Vec3{
         Vec3(self.0 * other.0, self.1 * other.1, self.2 * other.2)
     }
+
+    pub fn cross(&self, other: &Vec3) -> Vec3 {
+        Vec3(
+            self.1 * other.2 - self.2 * other.1,
+            self.2 * other.0 - self.0 * other.2,
+            self.0 * other.1 - self.1 * other.0,
+        )
+    }
+
+    pub fn min(&self, other: &Vec3) -> Vec3 {
+        Vec3(self.0.min(other.0), self.1.min(other.1), self.2.min(other.2))
+    }
+
+    pub fn max(&self, other: &Vec3) -> Vec3 {
+        Vec3(self.0.max(other.0), self.1.max(other.1), self.2.max(other.2))
+    }
+
+    pub fn axis(&self, axis: usize) -> f32 {
+        match axis {
+            0 => self.0,
+            1 => self.1,
+            _ => self.2,
+        }
+    }
 }
 
 impl Clone for Vec3 {
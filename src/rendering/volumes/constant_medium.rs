@@ -0,0 +1,58 @@
+use rand::Rng;
+use crate::rendering::materials::material::Material;
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+// Fills a closed boundary shape with a medium of constant density. The phase function decides how
+// light scatters inside of it, e.g. Isotropic or HenyeyGreenstein.
+pub struct ConstantMedium {
+    boundary: Box<dyn Hittable>,
+    density: f32,
+    phase_function: Box<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Box<dyn Hittable>, density: f32, phase_function: Box<dyn Material>) -> Self {
+        Self { boundary, density, phase_function }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let mut rng = random::rng();
+        let length = ray.direction().length();
+
+        for span in self.boundary.spans(ray, t_min, t_max) {
+            let enter = span.enter.t.max(t_min);
+            let exit = span.exit.t.min(t_max);
+            if exit <= enter {
+                continue;
+            }
+
+            let distance_inside = (exit - enter) * length;
+            let hit_distance = -(1.0 - rng.gen::<f32>()).ln() / self.density;
+            if hit_distance > distance_inside {
+                continue;
+            }
+
+            record.t = enter + hit_distance / length;
+            record.position = ray.at(record.t);
+            record.normal = Vec3(1.0, 0.0, 0.0);
+            record.font_face = true;
+            record.u = 0.0;
+            record.v = 0.0;
+            record.material = Some(self.phase_function.as_ref());
+
+            return true;
+        }
+
+        false
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,40 @@
+use rand::Rng;
+use crate::rendering::materials::henyey_greenstein::sample_henyey_greenstein;
+use crate::rendering::random;
+use crate::rendering::vector_math::Vec3;
+
+// Homogeneous medium filling the space between the surfaces of the scene. Rays get a chance to
+// scatter before they reach the next surface, so far away objects fade into the fog.
+pub struct Fog {
+    color: Vec3,
+    density: f32,
+    g: f32,
+}
+
+impl Fog {
+    pub fn new(color: Vec3, density: f32, g: f32) -> Self {
+        Self { color, density, g: g.clamp(-0.99, 0.99) }
+    }
+
+    pub fn sample_distance(&self) -> f32 {
+        let mut rng = random::rng();
+        -(1.0 - rng.gen::<f32>()).ln() / self.density
+    }
+
+    pub fn sample_direction(&self, direction: Vec3) -> Vec3 {
+        let mut rng = random::rng();
+        sample_henyey_greenstein(direction, self.g, (rng.gen(), rng.gen()))
+    }
+
+    pub fn color(&self) -> Vec3 {
+        self.color
+    }
+}
+
+impl Clone for Fog {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for Fog {}
@@ -0,0 +1,116 @@
+use rand::Rng;
+use crate::rendering::materials::material::{Material, ScatterRecord};
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+use crate::rendering::volumes::voxel_grid::VoxelGrid;
+
+fn grid_position(bounds: &Aabb, position: Vec3) -> Vec3 {
+    let size = bounds.maximum - bounds.minimum;
+    let relative = position - bounds.minimum;
+    Vec3(relative.0 / size.0, relative.1 / size.1, relative.2 / size.2)
+}
+
+struct VolumeMaterial {
+    phase_function: Box<dyn Material>,
+    emission: Option<(VoxelGrid, Vec3)>,
+    bounds: Aabb,
+}
+
+impl Material for VolumeMaterial {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        self.phase_function.sample(ray, hit_record, sampler)
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        self.phase_function.eval(ray, hit_record, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        self.phase_function.pdf(ray, hit_record, direction)
+    }
+
+    fn is_volume(&self) -> bool {
+        true
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Vec3 {
+        match &self.emission {
+            Some((grid, color)) => *color * grid.sample(grid_position(&self.bounds, hit_record.position)),
+            None => Vec3(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+// Medium with a density that varies across a voxel grid stretched over the bounds. Collisions are
+// found by delta tracking against the largest density of the grid.
+pub struct HeterogeneousMedium {
+    bounds: Aabb,
+    density: VoxelGrid,
+    density_scale: f32,
+    material: VolumeMaterial,
+}
+
+impl HeterogeneousMedium {
+    pub fn new(bounds: Aabb, density: VoxelGrid, density_scale: f32, phase_function: Box<dyn Material>) -> Self {
+        Self {
+            bounds,
+            density,
+            density_scale,
+            material: VolumeMaterial { phase_function, emission: None, bounds },
+        }
+    }
+
+    // Emits color scaled by the grid value wherever light collides with the medium, e.g. for fire
+    pub fn set_emission(&mut self, emission: VoxelGrid, color: Vec3) {
+        self.material.emission = Some((emission, color));
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        let (enter, exit) = match self.bounds.intersect(ray, t_min, t_max) {
+            Some(range) => range,
+            None => return false,
+        };
+
+        let majorant = self.density.maximum() * self.density_scale;
+        if majorant <= 0.0 {
+            return false;
+        }
+
+        let mut rng = random::rng();
+        let length = ray.direction().length();
+        let mut t = enter;
+
+        loop {
+            t += -(1.0 - rng.gen::<f32>()).ln() / (majorant * length);
+            if t >= exit {
+                return false;
+            }
+
+            // Accept the tentative collision in proportion to the real density, otherwise it was
+            // a null collision and the ray continues unchanged
+            let position = ray.at(t);
+            let density = self.density.sample(grid_position(&self.bounds, position)) * self.density_scale;
+            if rng.gen::<f32>() * majorant < density {
+                record.t = t;
+                record.position = position;
+                record.normal = Vec3(1.0, 0.0, 0.0);
+                record.font_face = true;
+                record.u = 0.0;
+                record.v = 0.0;
+                record.material = Some(&self.material);
+
+                return true;
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod constant_medium;
+pub mod fog;
+pub mod voxel_grid;
+pub mod heterogeneous_medium;
+pub mod subsurface;
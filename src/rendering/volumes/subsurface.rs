@@ -0,0 +1,233 @@
+use rand::Rng;
+use crate::rendering::materials::dielectric::Dielectric;
+use crate::rendering::materials::henyey_greenstein::sample_henyey_greenstein;
+use crate::rendering::materials::material::{Lobe, Material, ScatterRecord};
+use crate::rendering::random;
+use crate::rendering::ray::Ray;
+use crate::rendering::samplers::sampler::Sampler;
+use crate::rendering::shapes::aabb::Aabb;
+use crate::rendering::shapes::hittable::{HitRecord, Hittable};
+use crate::rendering::vector_math::Vec3;
+
+fn exp(v: Vec3) -> Vec3 {
+    Vec3(v.0.exp(), v.1.exp(), v.2.exp())
+}
+
+fn average(v: Vec3) -> f32 {
+    (v.0 + v.1 + v.2) / 3.0
+}
+
+// Albedo a single scattering event needs for the whole volume to look like the given albedo after
+// many bounces (Chiang et al. 2016)
+fn single_scattering_albedo(albedo: f32) -> f32 {
+    let albedo = albedo.clamp(0.0, 0.999);
+    let root = 4.09712 + 4.20863 * albedo - (9.59217 + 41.6808 * albedo + 17.7126 * albedo * albedo).sqrt();
+    1.0 - root * root
+}
+
+// The medium scatters each color differently far. Distances are sampled for a random channel and
+// weighted against the average over all channels, so no channel gets a zero probability.
+struct Coefficients {
+    extinction: Vec3,
+    albedo: Vec3,
+}
+
+impl Coefficients {
+    fn transmittance(&self, distance: f32) -> Vec3 {
+        exp(self.extinction * -distance)
+    }
+
+    fn sample_distance(&self) -> f32 {
+        let mut rng = random::rng();
+        let extinction = self.extinction.axis(rng.gen_range(0..3));
+        -(1.0 - rng.gen::<f32>()).ln() / extinction
+    }
+
+    // Weight of a scattering event after the distance
+    fn scatter_weight(&self, distance: f32) -> Vec3 {
+        let transmittance = self.transmittance(distance);
+        let pdf = average(self.extinction.mul(&transmittance));
+        self.extinction.mul(&self.albedo).mul(&transmittance) / pdf
+    }
+
+    // Weight of passing the distance without scattering
+    fn pass_weight(&self, distance: f32) -> Vec3 {
+        let transmittance = self.transmittance(distance);
+        transmittance / average(transmittance)
+    }
+}
+
+impl Clone for Coefficients {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for Coefficients {}
+
+// Scattering event inside of the volume. Shadow rays can't leave through the boundary, so it can't
+// be sampled towards lights and only reports a pdf of zero.
+struct SubsurfaceMedium {
+    coefficients: Coefficients,
+    g: f32,
+}
+
+impl Material for SubsurfaceMedium {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let distance = hit_record.distance_inside;
+        let direction = sample_henyey_greenstein(ray.direction(), self.g, sampler.get_2d());
+        Some(ScatterRecord::new(direction, self.coefficients.scatter_weight(distance), 0.0, Lobe::Diffuse))
+    }
+
+    fn is_volume(&self) -> bool {
+        true
+    }
+}
+
+// Refracting surface of the volume. Rays hitting it from the inside have crossed the volume
+// without scattering.
+struct SubsurfaceSurface {
+    coefficients: Coefficients,
+    interface: Dielectric,
+}
+
+impl SubsurfaceSurface {
+    fn pass_weight(&self, hit_record: &HitRecord) -> Vec3 {
+        if hit_record.font_face {
+            Vec3(1.0, 1.0, 1.0)
+        } else {
+            self.coefficients.pass_weight(hit_record.distance_inside)
+        }
+    }
+}
+
+impl Material for SubsurfaceSurface {
+    fn sample(&self, ray: &Ray, hit_record: &HitRecord, sampler: &mut dyn Sampler) -> Option<ScatterRecord> {
+        let mut sample = self.interface.sample(ray, hit_record, sampler)?;
+        sample.weight = sample.weight.mul(&self.pass_weight(hit_record));
+        Some(sample)
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> Vec3 {
+        self.interface.eval(ray, hit_record, direction).mul(&self.pass_weight(hit_record))
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, direction: Vec3) -> f32 {
+        self.interface.pdf(ray, hit_record, direction)
+    }
+}
+
+// Translucent solid like skin, wax or marble. Light refracts into the closed boundary and takes a
+// random walk through a dense medium before leaving it somewhere else. The albedo is the color the
+// object ends up with, the radius is how far light of each color travels on average between two
+// scattering events.
+pub struct Subsurface {
+    boundary: Box<dyn Hittable>,
+    coefficients: Coefficients,
+    ior: f32,
+    medium: SubsurfaceMedium,
+    surface: SubsurfaceSurface,
+}
+
+impl Subsurface {
+    pub fn new(boundary: Box<dyn Hittable>, albedo: Vec3, radius: Vec3, ior: f32) -> Self {
+        let coefficients = Coefficients {
+            extinction: Vec3(1.0 / radius.0.max(1e-4), 1.0 / radius.1.max(1e-4), 1.0 / radius.2.max(1e-4)),
+            albedo: Vec3(single_scattering_albedo(albedo.0), single_scattering_albedo(albedo.1), single_scattering_albedo(albedo.2)),
+        };
+
+        Self {
+            boundary,
+            coefficients,
+            ior,
+            medium: SubsurfaceMedium { coefficients, g: 0.0 },
+            surface: SubsurfaceSurface { coefficients, interface: Dielectric::new(ior, 0.0) },
+        }
+    }
+
+    // Forward scattering (g > 0) lets light travel deeper, as in skin
+    pub fn set_anisotropy(&mut self, g: f32) {
+        self.medium.g = g.clamp(-0.99, 0.99);
+    }
+
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.surface.interface = Dielectric::new(self.ior, roughness);
+    }
+}
+
+impl Hittable for Subsurface {
+    fn hit<'a, 'b>(&'a self, ray: &Ray, t_min: f32, t_max: f32, record: &mut HitRecord<'b>) -> bool where 'a: 'b {
+        if !self.boundary.hit(ray, t_min, t_max, record) {
+            return false;
+        }
+
+        // Hitting the back of the boundary means the ray travels through the volume. It entered
+        // where the span ending here starts, or at its origin if it started inside.
+        if !record.font_face {
+            let length = ray.direction().length();
+            let enter = self.boundary.spans(ray, 0.0, record.t).last().map_or(0.0, |span| span.enter.t);
+            let inside = (record.t - enter) * length;
+            let distance = self.coefficients.sample_distance();
+            if distance < inside {
+                record.t = (enter + distance / length).max(t_min);
+                record.position = ray.at(record.t);
+                record.normal = Vec3(1.0, 0.0, 0.0);
+                record.font_face = true;
+                record.u = 0.0;
+                record.v = 0.0;
+                record.distance_inside = distance;
+                record.material = Some(&self.medium);
+                return true;
+            }
+            record.distance_inside = inside;
+        }
+
+        record.material = Some(&self.surface);
+        true
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::shapes::sphere::Sphere;
+    use crate::rendering::shapes::testing::{close, gray};
+
+    // Light travels so far between scattering events that rays always pass through
+    fn clear_ball() -> Subsurface {
+        Subsurface::new(Box::new(Sphere::new(Vec3(0.0, 0.0, 0.0), 1.0, gray())), Vec3(0.5, 0.5, 0.5), Vec3(1e9, 1e9, 1e9), 1.5)
+    }
+
+    #[test]
+    fn measures_from_the_entry_of_a_ray_starting_outside() {
+        let ball = clear_ball();
+        let ray = Ray::new(Vec3(-5.0, 0.0, 0.0), Vec3(2.0, 0.0, 0.0), 0.0);
+
+        let mut record = HitRecord::new();
+        assert!(ball.hit(&ray, 0.001, f32::INFINITY, &mut record));
+        assert!(close(record.t, 2.0));
+        assert!(record.font_face);
+
+        // Looking past the entry, as when walking along the ray, still counts from the entry
+        let mut record = HitRecord::new();
+        assert!(ball.hit(&ray, 2.5, f32::INFINITY, &mut record));
+        assert!(close(record.t, 3.0));
+        assert!(!record.font_face);
+        assert!(close(record.distance_inside, 2.0));
+    }
+
+    #[test]
+    fn measures_from_the_origin_of_a_ray_starting_inside() {
+        let ball = clear_ball();
+        let ray = Ray::new(Vec3(0.5, 0.0, 0.0), Vec3(-1.0, 0.0, 0.0), 0.0);
+
+        let mut record = HitRecord::new();
+        assert!(ball.hit(&ray, 0.001, f32::INFINITY, &mut record));
+        assert!(close(record.t, 1.5));
+        assert!(close(record.distance_inside, 1.5));
+    }
+}
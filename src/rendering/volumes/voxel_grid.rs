@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::Path;
+use crate::rendering::vector_math::Vec3;
+
+// Dense grid of values with x varying fastest, then y, then z
+pub struct VoxelGrid {
+    resolution: (usize, usize, usize),
+    values: Vec<f32>,
+    maximum: f32,
+}
+
+impl VoxelGrid {
+    pub fn new(resolution: (usize, usize, usize), values: Vec<f32>) -> Result<Self, String> {
+        let expected = resolution.0 * resolution.1 * resolution.2;
+        if expected == 0 || values.len() != expected {
+            return Err(format!("voxel grid of {}x{}x{} needs {} values, got {}", resolution.0, resolution.1, resolution.2, expected, values.len()));
+        }
+
+        let maximum = values.iter().fold(0.0f32, |maximum, value| maximum.max(*value));
+        Ok(Self { resolution, values, maximum })
+    }
+
+    // Text files start with the resolution "nx ny nz" followed by all values separated by
+    // whitespace. Lines starting with '#' are ignored.
+    pub fn load_text(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut tokens = content.lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(|line| line.split_whitespace());
+
+        let mut dimension = || -> Result<usize, String> {
+            tokens.next()
+                .ok_or_else(|| "voxel grid is missing its resolution".to_string())?
+                .parse::<usize>()
+                .map_err(|e| e.to_string())
+        };
+        let resolution = (dimension()?, dimension()?, dimension()?);
+
+        let values = tokens
+            .map(|token| token.parse::<f32>().map_err(|e| e.to_string()))
+            .collect::<Result<Vec<f32>, String>>()?;
+
+        VoxelGrid::new(resolution, values)
+    }
+
+    // Raw files only contain little endian f32 values, so the resolution has to be known
+    pub fn load_raw(path: &Path, resolution: (usize, usize, usize)) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        if bytes.len() % 4 != 0 {
+            return Err(format!("raw voxel grid has {} bytes, which is not a multiple of 4", bytes.len()));
+        }
+
+        let values = bytes.chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+
+        VoxelGrid::new(resolution, values)
+    }
+
+    pub fn maximum(&self) -> f32 {
+        self.maximum
+    }
+
+    fn value(&self, x: usize, y: usize, z: usize) -> f32 {
+        self.values[x + self.resolution.0 * (y + self.resolution.1 * z)]
+    }
+
+    // Trilinear lookup with each coordinate of the position going from 0 to 1 across the grid
+    pub fn sample(&self, position: Vec3) -> f32 {
+        let continuous = |coordinate: f32, size: usize| -> (usize, usize, f32) {
+            let scaled = (coordinate * size as f32 - 0.5).clamp(0.0, (size - 1) as f32);
+            let lower = scaled.floor() as usize;
+            let upper = (lower + 1).min(size - 1);
+            (lower, upper, scaled - lower as f32)
+        };
+
+        let (x0, x1, fx) = continuous(position.0, self.resolution.0);
+        let (y0, y1, fy) = continuous(position.1, self.resolution.1);
+        let (z0, z1, fz) = continuous(position.2, self.resolution.2);
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+        let bottom = lerp(
+            lerp(self.value(x0, y0, z0), self.value(x1, y0, z0), fx),
+            lerp(self.value(x0, y1, z0), self.value(x1, y1, z0), fx),
+            fy,
+        );
+        let top = lerp(
+            lerp(self.value(x0, y0, z1), self.value(x1, y0, z1), fx),
+            lerp(self.value(x0, y1, z1), self.value(x1, y1, z1), fx),
+            fy,
+        );
+
+        lerp(bottom, top, fz)
+    }
+}